@@ -0,0 +1,77 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+fn decode_sextet(c: u8) -> Option<u32> {
+    match c {
+        b'A'..=b'Z' => Some(u32::from(c - b'A')),
+        b'a'..=b'z' => Some(u32::from(c - b'a') + 26),
+        b'0'..=b'9' => Some(u32::from(c - b'0') + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes standard (RFC 4648 section 4) base64, padding is optional
+pub(crate) fn decode(input: &[u8]) -> Option<Vec<u8>> {
+    let input = match input.iter().position(|c| *c == b'=') {
+        Some(idx) => {
+            let padding = &input[idx..];
+            if padding.len() > 2 || padding.iter().any(|c| *c != b'=') || input.len() % 4 != 0 {
+                return None;
+            }
+            &input[..idx]
+        }
+        None => input,
+    };
+
+    if input.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+
+    for chunk in input.chunks(4) {
+        let mut bits: u32 = 0;
+        for (idx, c) in chunk.iter().enumerate() {
+            bits |= decode_sextet(*c)? << (18 - 6 * idx);
+        }
+        let bytes = bits.to_be_bytes();
+        output.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+
+    Some(output)
+}
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes to standard (RFC 4648 section 4) base64 with padding
+pub(crate) fn encode(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let mut bytes = [0u8; 4];
+        bytes[1..chunk.len() + 1].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes(bytes);
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                output.push(ALPHABET[(bits >> (18 - 6 * idx) & 0x3f) as usize]);
+            } else {
+                output.push(b'=');
+            }
+        }
+    }
+
+    output
+}
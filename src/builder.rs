@@ -0,0 +1,401 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    time::{NtpTime, TimeDescription, TimeZoneAdjustment},
+    Attribute, Bandwidth, BandwidthModifier, ConnectionData, Contact, KeyMethod, Media, Origin,
+    Sdp, SdpError,
+};
+
+fn address_type_of(address: &[u8]) -> &'static [u8] {
+    if address.contains(&b':') {
+        b"IP6"
+    } else {
+        b"IP4"
+    }
+}
+
+fn connection_of(address: &[u8]) -> ConnectionData<'_> {
+    ConnectionData {
+        network_type: b"IN",
+        address_type: address_type_of(address),
+        connection_address: address,
+    }
+}
+
+fn bandwidths_in_range(bandwidths: &[Bandwidth]) -> bool {
+    bandwidths
+        .iter()
+        .all(|bandwidth| bandwidth.value <= bandwidth.modifier.max_value())
+}
+
+/// Builds a session description field by field.
+///
+/// Starts out with `v=0`, `s=-` and `t=0 0`, an origin must be provided
+/// before calling `build()`.
+pub struct SdpBuilder<'a> {
+    version: &'a [u8],
+    origin: Option<Origin<'a>>,
+    session_name: &'a [u8],
+    information: Option<&'a [u8]>,
+    uri: Option<&'a [u8]>,
+    emails: Vec<Contact<'a>>,
+    phones: Vec<Contact<'a>>,
+    connection: Option<ConnectionData<'a>>,
+    bandwidths: Vec<Bandwidth<'a>>,
+    times: Vec<TimeDescription>,
+    time_zone_adjustments: Vec<TimeZoneAdjustment>,
+    encryption_key: Option<KeyMethod<'a>>,
+    attributes: Vec<Attribute<'a>>,
+    medias: Vec<MediaBuilder<'a>>,
+}
+
+impl<'a> SdpBuilder<'a> {
+    pub fn new() -> SdpBuilder<'a> {
+        SdpBuilder {
+            version: b"0",
+            origin: None,
+            session_name: b"-",
+            information: None,
+            uri: None,
+            emails: Vec::new(),
+            phones: Vec::new(),
+            connection: None,
+            bandwidths: Vec::new(),
+            times: Vec::new(),
+            time_zone_adjustments: Vec::new(),
+            encryption_key: None,
+            attributes: Vec::new(),
+            medias: Vec::new(),
+        }
+    }
+
+    /// Sets `o=` with network type `IN`, address type is deduced from `unicast_address`
+    pub fn origin<U, I, V, A>(
+        self,
+        user_id: &'a U,
+        session_id: &'a I,
+        session_version: &'a V,
+        unicast_address: &'a A,
+    ) -> SdpBuilder<'a>
+    where
+        U: AsRef<[u8]> + ?Sized,
+        I: AsRef<[u8]> + ?Sized,
+        V: AsRef<[u8]> + ?Sized,
+        A: AsRef<[u8]> + ?Sized,
+    {
+        let unicast_address = unicast_address.as_ref();
+        self.with_origin(Origin {
+            user_id: user_id.as_ref(),
+            session_id: session_id.as_ref(),
+            session_version: session_version.as_ref(),
+            network_type: b"IN",
+            address_type: address_type_of(unicast_address),
+            unicast_address,
+        })
+    }
+
+    pub fn with_origin(mut self, origin: Origin<'a>) -> SdpBuilder<'a> {
+        self.origin.replace(origin);
+        self
+    }
+
+    /// Sets `s=`, `build()` fails when it is empty
+    pub fn session_name<S>(mut self, session_name: &'a S) -> SdpBuilder<'a>
+    where
+        S: AsRef<[u8]> + ?Sized,
+    {
+        self.session_name = session_name.as_ref();
+        self
+    }
+
+    /// Sets session level `i=`
+    pub fn information<S>(mut self, information: &'a S) -> SdpBuilder<'a>
+    where
+        S: AsRef<[u8]> + ?Sized,
+    {
+        self.information.replace(information.as_ref());
+        self
+    }
+
+    /// Sets `u=`
+    pub fn uri<S>(mut self, uri: &'a S) -> SdpBuilder<'a>
+    where
+        S: AsRef<[u8]> + ?Sized,
+    {
+        self.uri.replace(uri.as_ref());
+        self
+    }
+
+    /// Adds an `e=` line without display name
+    pub fn email<A>(self, address: &'a A) -> SdpBuilder<'a>
+    where
+        A: AsRef<[u8]> + ?Sized,
+    {
+        self.with_email(Contact {
+            address: address.as_ref(),
+            display_name: None,
+        })
+    }
+
+    pub fn with_email(mut self, email: Contact<'a>) -> SdpBuilder<'a> {
+        self.emails.push(email);
+        self
+    }
+
+    /// Adds a `p=` line without display name
+    pub fn phone<A>(self, address: &'a A) -> SdpBuilder<'a>
+    where
+        A: AsRef<[u8]> + ?Sized,
+    {
+        self.with_phone(Contact {
+            address: address.as_ref(),
+            display_name: None,
+        })
+    }
+
+    pub fn with_phone(mut self, phone: Contact<'a>) -> SdpBuilder<'a> {
+        self.phones.push(phone);
+        self
+    }
+
+    /// Sets session level `c=` with network type `IN`, address type is deduced from `address`
+    pub fn connection<A>(self, address: &'a A) -> SdpBuilder<'a>
+    where
+        A: AsRef<[u8]> + ?Sized,
+    {
+        self.with_connection(connection_of(address.as_ref()))
+    }
+
+    pub fn with_connection(mut self, connection: ConnectionData<'a>) -> SdpBuilder<'a> {
+        self.connection.replace(connection);
+        self
+    }
+
+    /// Adds a session level `b=` line, `build()` fails when a CT, AS, RS or
+    /// RR value does not fit in a u32
+    pub fn bandwidth(mut self, modifier: BandwidthModifier<'a>, value: u64) -> SdpBuilder<'a> {
+        self.bandwidths.push(Bandwidth { modifier, value });
+        self
+    }
+
+    /// Adds a `t=` line
+    pub fn time(self, start_time: NtpTime, stop_time: NtpTime) -> SdpBuilder<'a> {
+        self.with_time(TimeDescription {
+            start_time,
+            stop_time,
+            repeats: Vec::new(),
+        })
+    }
+
+    /// Adds a `t=` line along with its `r=` lines
+    pub fn with_time(mut self, time_description: TimeDescription) -> SdpBuilder<'a> {
+        self.times.push(time_description);
+        self
+    }
+
+    /// Adds an adjustment to the `z=` line
+    pub fn time_zone_adjustment(mut self, at: NtpTime, offset: i64) -> SdpBuilder<'a> {
+        self.time_zone_adjustments
+            .push(TimeZoneAdjustment { at, offset });
+        self
+    }
+
+    /// Sets session level `k=`, deprecated by RFC 8866
+    pub fn encryption_key(mut self, encryption_key: KeyMethod<'a>) -> SdpBuilder<'a> {
+        self.encryption_key.replace(encryption_key);
+        self
+    }
+
+    /// Adds a session level `a=` line, `attribute` being everything after `a=`
+    pub fn attribute<S>(self, attribute: &'a S) -> SdpBuilder<'a>
+    where
+        S: AsRef<[u8]> + ?Sized,
+    {
+        self.with_attribute(Attribute::parse(attribute.as_ref()))
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute<'a>) -> SdpBuilder<'a> {
+        self.attributes.push(attribute);
+        self
+    }
+
+    pub fn media(mut self, media: MediaBuilder<'a>) -> SdpBuilder<'a> {
+        self.medias.push(media);
+        self
+    }
+
+    /// Checks the mandatory fields of RFC 4566 and assembles the `Sdp`
+    pub fn build(self) -> Result<Sdp<'a>, SdpError> {
+        let origin = self.origin.ok_or(SdpError::MissingField('o'))?;
+        if self.session_name.is_empty() {
+            return Err(SdpError::MissingField('s'));
+        }
+
+        if !bandwidths_in_range(&self.bandwidths) {
+            return Err(SdpError::InvalidField('b'));
+        }
+
+        let mut medias = Vec::with_capacity(self.medias.len());
+        for media in self.medias {
+            if media.formats.is_empty() {
+                return Err(SdpError::MissingField('m'));
+            }
+            if !bandwidths_in_range(&media.bandwidths) {
+                return Err(SdpError::InvalidField('b'));
+            }
+            if self.connection.is_none() && media.connection.is_none() {
+                return Err(SdpError::MissingField('c'));
+            }
+            medias.push(Media {
+                media_type: media.media_type,
+                port: media.port,
+                number_of_ports: media.number_of_ports,
+                protocol: media.protocol,
+                formats: media.formats,
+                title: media.title,
+                connection: media.connection,
+                bandwidths: media.bandwidths,
+                encryption_key: media.encryption_key,
+                attributes: media.attributes,
+            });
+        }
+
+        Ok(Sdp {
+            version: self.version,
+            origin,
+            session_name: self.session_name,
+            information: self.information,
+            uri: self.uri,
+            emails: self.emails,
+            phones: self.phones,
+            connection: self.connection,
+            bandwidths: self.bandwidths,
+            times: if self.times.is_empty() {
+                vec![TimeDescription {
+                    start_time: NtpTime::Unbounded,
+                    stop_time: NtpTime::Unbounded,
+                    repeats: Vec::new(),
+                }]
+            } else {
+                self.times
+            },
+            time_zone_adjustments: self.time_zone_adjustments,
+            encryption_key: self.encryption_key,
+            attributes: self.attributes,
+            medias,
+        })
+    }
+}
+
+impl<'a> Default for SdpBuilder<'a> {
+    fn default() -> SdpBuilder<'a> {
+        SdpBuilder::new()
+    }
+}
+
+/// Builds a media description, to be added with `SdpBuilder::media()`.
+pub struct MediaBuilder<'a> {
+    media_type: &'a [u8],
+    port: u16,
+    number_of_ports: i32,
+    protocol: &'a [u8],
+    formats: Vec<&'a [u8]>,
+    title: Option<&'a [u8]>,
+    connection: Option<ConnectionData<'a>>,
+    bandwidths: Vec<Bandwidth<'a>>,
+    encryption_key: Option<KeyMethod<'a>>,
+    attributes: Vec<Attribute<'a>>,
+}
+
+impl<'a> MediaBuilder<'a> {
+    pub fn new<T, P>(media_type: &'a T, port: u16, protocol: &'a P) -> MediaBuilder<'a>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        P: AsRef<[u8]> + ?Sized,
+    {
+        MediaBuilder {
+            media_type: media_type.as_ref(),
+            port,
+            number_of_ports: 1,
+            protocol: protocol.as_ref(),
+            formats: Vec::new(),
+            title: None,
+            connection: None,
+            bandwidths: Vec::new(),
+            encryption_key: None,
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn number_of_ports(mut self, number_of_ports: i32) -> MediaBuilder<'a> {
+        self.number_of_ports = number_of_ports;
+        self
+    }
+
+    pub fn format<F>(mut self, format: &'a F) -> MediaBuilder<'a>
+    where
+        F: AsRef<[u8]> + ?Sized,
+    {
+        self.formats.push(format.as_ref());
+        self
+    }
+
+    /// Sets media level `i=`
+    pub fn title<S>(mut self, title: &'a S) -> MediaBuilder<'a>
+    where
+        S: AsRef<[u8]> + ?Sized,
+    {
+        self.title.replace(title.as_ref());
+        self
+    }
+
+    /// Sets media level `c=` with network type `IN`, address type is deduced from `address`
+    pub fn connection<A>(self, address: &'a A) -> MediaBuilder<'a>
+    where
+        A: AsRef<[u8]> + ?Sized,
+    {
+        self.with_connection(connection_of(address.as_ref()))
+    }
+
+    pub fn with_connection(mut self, connection: ConnectionData<'a>) -> MediaBuilder<'a> {
+        self.connection.replace(connection);
+        self
+    }
+
+    /// Adds a media level `b=` line, see `SdpBuilder::bandwidth()`
+    pub fn bandwidth(mut self, modifier: BandwidthModifier<'a>, value: u64) -> MediaBuilder<'a> {
+        self.bandwidths.push(Bandwidth { modifier, value });
+        self
+    }
+
+    /// Sets media level `k=`, deprecated by RFC 8866
+    pub fn encryption_key(mut self, encryption_key: KeyMethod<'a>) -> MediaBuilder<'a> {
+        self.encryption_key.replace(encryption_key);
+        self
+    }
+
+    /// Adds a media level `a=` line, `attribute` being everything after `a=`
+    pub fn attribute<S>(self, attribute: &'a S) -> MediaBuilder<'a>
+    where
+        S: AsRef<[u8]> + ?Sized,
+    {
+        self.with_attribute(Attribute::parse(attribute.as_ref()))
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute<'a>) -> MediaBuilder<'a> {
+        self.attributes.push(attribute);
+        self
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::{Attribute, ConnectionData, Media, Sdp};
+
+/// `a=sendrecv`, `a=sendonly`, `a=recvonly` or `a=inactive`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Direction {
+    SendRecv,
+    SendOnly,
+    RecvOnly,
+    Inactive,
+}
+
+impl Direction {
+    pub fn from_bytes(name: &[u8]) -> Option<Direction> {
+        match name {
+            b"sendrecv" => Some(Direction::SendRecv),
+            b"sendonly" => Some(Direction::SendOnly),
+            b"recvonly" => Some(Direction::RecvOnly),
+            b"inactive" => Some(Direction::Inactive),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Direction::SendRecv => b"sendrecv",
+            Direction::SendOnly => b"sendonly",
+            Direction::RecvOnly => b"recvonly",
+            Direction::Inactive => b"inactive",
+        }
+    }
+
+    pub fn is_sending(&self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::SendOnly)
+    }
+
+    pub fn is_receiving(&self) -> bool {
+        matches!(self, Direction::SendRecv | Direction::RecvOnly)
+    }
+
+    /// The direction as seen from the other end, as an answer would mirror it
+    pub fn reversed(&self) -> Direction {
+        match self {
+            Direction::SendOnly => Direction::RecvOnly,
+            Direction::RecvOnly => Direction::SendOnly,
+            direction => *direction,
+        }
+    }
+
+    fn without_receiving(&self) -> Direction {
+        match self {
+            Direction::SendRecv => Direction::SendOnly,
+            Direction::RecvOnly => Direction::Inactive,
+            direction => *direction,
+        }
+    }
+
+    fn find(attributes: &[Attribute]) -> Option<Direction> {
+        attributes
+            .iter()
+            .filter(|attribute| attribute.value.is_none())
+            .find_map(|attribute| Direction::from_bytes(attribute.name))
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+/// How to read the RFC 2543 hold convention, a `c=IN IP4 0.0.0.0` connection.
+///
+/// RFC 3264 section 8.4 replaced it with direction attributes, yet older
+/// peers still put a stream on hold this way.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum LegacyHold {
+    /// Take the direction attributes as they are
+    Ignore,
+    /// The sender does not want to receive, `sendrecv` becomes `sendonly`
+    /// and `recvonly` becomes `inactive`
+    #[default]
+    SendOnly,
+    /// The stream is on hold in both directions
+    Inactive,
+}
+
+fn is_legacy_hold(connection: &ConnectionData) -> bool {
+    connection.address_type == b"IP4" && connection.connection_address == b"0.0.0.0"
+}
+
+impl<'a> Sdp<'a> {
+    /// Session level direction attribute, if any
+    pub fn direction(&self) -> Option<Direction> {
+        Direction::find(&self.attributes)
+    }
+}
+
+impl<'a> Media<'a> {
+    /// Media level direction attribute, if any
+    pub fn direction(&self) -> Option<Direction> {
+        Direction::find(&self.attributes)
+    }
+
+    /// Same as `effective_direction_with()` with the default `LegacyHold::SendOnly`
+    pub fn effective_direction(&self, sdp: &Sdp) -> Direction {
+        self.effective_direction_with(sdp, LegacyHold::default())
+    }
+
+    /// Direction of this stream within `sdp`.
+    ///
+    /// The media level attribute wins over the session level one, and
+    /// `sendrecv` is assumed when neither is present (RFC 4566 section 6).
+    /// A port of 0 means the stream is rejected or disabled and therefore
+    /// inactive.
+    pub fn effective_direction_with(&self, sdp: &Sdp, legacy_hold: LegacyHold) -> Direction {
+        if self.port == 0 {
+            return Direction::Inactive;
+        }
+
+        let direction = self
+            .direction()
+            .or_else(|| sdp.direction())
+            .unwrap_or(Direction::SendRecv);
+
+        match self.connection.as_ref().or(sdp.connection.as_ref()) {
+            Some(connection) if is_legacy_hold(connection) => match legacy_hold {
+                LegacyHold::Ignore => direction,
+                LegacyHold::SendOnly => direction.without_receiving(),
+                LegacyHold::Inactive => Direction::Inactive,
+            },
+            _ => direction,
+        }
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt, io};
+
+use crate::{
+    hex::{parse_hex_pairs, write_hex_pairs},
+    Attribute, Media, Sdp,
+};
+
+/// Hash function of a fingerprint, names are compared case-insensitively
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashFunction<'a> {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Md5,
+    Md2,
+    Other(&'a [u8]),
+}
+
+impl<'a> HashFunction<'a> {
+    pub fn from_bytes(name: &'a [u8]) -> HashFunction<'a> {
+        match name.to_ascii_lowercase().as_slice() {
+            b"sha-1" => HashFunction::Sha1,
+            b"sha-224" => HashFunction::Sha224,
+            b"sha-256" => HashFunction::Sha256,
+            b"sha-384" => HashFunction::Sha384,
+            b"sha-512" => HashFunction::Sha512,
+            b"md5" => HashFunction::Md5,
+            b"md2" => HashFunction::Md2,
+            _ => HashFunction::Other(name),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            HashFunction::Sha1 => b"sha-1",
+            HashFunction::Sha224 => b"sha-224",
+            HashFunction::Sha256 => b"sha-256",
+            HashFunction::Sha384 => b"sha-384",
+            HashFunction::Sha512 => b"sha-512",
+            HashFunction::Md5 => b"md5",
+            HashFunction::Md2 => b"md2",
+            HashFunction::Other(name) => name,
+        }
+    }
+
+    /// Digest length in octets, `None` for unknown functions
+    pub fn digest_len(&self) -> Option<usize> {
+        match self {
+            HashFunction::Sha1 => Some(20),
+            HashFunction::Sha224 => Some(28),
+            HashFunction::Sha256 => Some(32),
+            HashFunction::Sha384 => Some(48),
+            HashFunction::Sha512 => Some(64),
+            HashFunction::Md5 | HashFunction::Md2 => Some(16),
+            HashFunction::Other(_) => None,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for HashFunction<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+/// Value of an `a=fingerprint` line, RFC 8122 section 5
+#[derive(Clone, PartialEq, Eq)]
+pub struct Fingerprint<'a> {
+    pub hash_function: HashFunction<'a>,
+    pub digest: Vec<u8>,
+}
+
+impl<'a> Fingerprint<'a> {
+    /// Fails when the digest length does not fit a known hash function
+    pub fn parse(value: &'a [u8]) -> Option<Fingerprint<'a>> {
+        let idx = value.iter().position(|c| *c == b' ')?;
+        if idx == 0 {
+            return None;
+        }
+        let hash_function = HashFunction::from_bytes(&value[..idx]);
+        let digest = parse_hex_pairs(&value[idx + 1..])?;
+        match hash_function.digest_len() {
+            Some(len) if len != digest.len() => None,
+            _ => Some(Fingerprint {
+                hash_function,
+                digest,
+            }),
+        }
+    }
+
+    /// Writes the `a=fingerprint` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"a=fingerprint:")?;
+        w.write_all(self.hash_function.as_bytes())?;
+        w.write_all(b" ")?;
+        write_hex_pairs(w, &self.digest)?;
+        w.write_all(b"\r\n")
+    }
+}
+
+impl<'a> fmt::Debug for Fingerprint<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digest = Vec::new();
+        write_hex_pairs(&mut digest, &self.digest).map_err(|_| fmt::Error)?;
+        f.debug_struct("Fingerprint")
+            .field("hash_function", &self.hash_function)
+            .field("digest", &String::from_utf8_lossy(&digest))
+            .finish()
+    }
+}
+
+impl<'a> fmt::Display for Fingerprint<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Returned by `Media::verify_certificate()`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FingerprintError {
+    /// No well-formed `a=fingerprint` at media or session level
+    NoFingerprint,
+    /// None of the advertised hash functions is supported by the caller
+    UnsupportedHashFunction,
+    /// The certificate matches none of the fingerprints
+    Mismatch,
+}
+
+impl fmt::Display for FingerprintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FingerprintError::NoFingerprint => f.write_str("No fingerprint"),
+            FingerprintError::UnsupportedHashFunction => {
+                f.write_str("No fingerprint with a supported hash function")
+            }
+            FingerprintError::Mismatch => f.write_str("Certificate does not match fingerprint"),
+        }
+    }
+}
+
+impl std::error::Error for FingerprintError {}
+
+fn fingerprints_of<'a>(attributes: &[Attribute<'a>]) -> Vec<Fingerprint<'a>> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.name == b"fingerprint")
+        .filter_map(|attribute| Fingerprint::parse(attribute.value?))
+        .collect()
+}
+
+/// `tls-id-value = 20*255(tls-id-char)` of RFC 8842 section 4
+fn is_tls_id(value: &[u8]) -> bool {
+    (20..=255).contains(&value.len())
+        && value
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'-' | b'_'))
+}
+
+fn tls_id_of<'a>(attributes: &[Attribute<'a>]) -> Option<&'a [u8]> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == b"tls-id")?
+        .value
+        .filter(|value| is_tls_id(value))
+}
+
+impl<'a> Sdp<'a> {
+    /// Well-formed session level `a=fingerprint` attributes
+    pub fn fingerprints(&self) -> Vec<Fingerprint<'a>> {
+        fingerprints_of(&self.attributes)
+    }
+}
+
+impl<'a> Media<'a> {
+    /// Well-formed `a=fingerprint` attributes of this media, or the session
+    /// level ones when it has none
+    pub fn fingerprints(&self, sdp: &Sdp<'a>) -> Vec<Fingerprint<'a>> {
+        let fingerprints = fingerprints_of(&self.attributes);
+        if fingerprints.is_empty() {
+            sdp.fingerprints()
+        } else {
+            fingerprints
+        }
+    }
+
+    /// `a=tls-id` of this media, falling back to the session level one
+    pub fn tls_id(&self, sdp: &Sdp<'a>) -> Option<&'a [u8]> {
+        tls_id_of(&self.attributes).or_else(|| tls_id_of(&sdp.attributes))
+    }
+
+    /// Checks the DER encoded certificate of the peer against the fingerprints.
+    ///
+    /// `hash` computes a digest with the given function, returning `None`
+    /// for functions it does not implement. RFC 8122 section 5 requires a
+    /// match with one of the fingerprints using a supported function.
+    pub fn verify_certificate<F>(
+        &self,
+        sdp: &Sdp<'a>,
+        certificate: &[u8],
+        mut hash: F,
+    ) -> Result<(), FingerprintError>
+    where
+        F: FnMut(HashFunction, &[u8]) -> Option<Vec<u8>>,
+    {
+        let fingerprints = self.fingerprints(sdp);
+        if fingerprints.is_empty() {
+            return Err(FingerprintError::NoFingerprint);
+        }
+
+        let mut supported = false;
+        for fingerprint in &fingerprints {
+            if let Some(digest) = hash(fingerprint.hash_function, certificate) {
+                if digest == fingerprint.digest {
+                    return Ok(());
+                }
+                supported = true;
+            }
+        }
+
+        if supported {
+            Err(FingerprintError::Mismatch)
+        } else {
+            Err(FingerprintError::UnsupportedHashFunction)
+        }
+    }
+}
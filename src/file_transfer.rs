@@ -0,0 +1,402 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt, io};
+
+use crate::{
+    hex::{hex_value, parse_hex_pairs, write_hex_pairs},
+    parse_number, Attribute, Media,
+};
+
+/// Splits at spaces outside of double quotes
+fn split_selectors(value: &[u8]) -> Vec<&[u8]> {
+    let mut selectors = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (idx, c) in value.iter().enumerate() {
+        match c {
+            b'"' => quoted = !quoted,
+            b' ' if !quoted => {
+                if idx > start {
+                    selectors.push(&value[start..idx]);
+                }
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    if value.len() > start {
+        selectors.push(&value[start..]);
+    }
+    selectors
+}
+
+fn unquote(value: &[u8]) -> Option<&[u8]> {
+    value.strip_prefix(b"\"")?.strip_suffix(b"\"")
+}
+
+/// Decodes the percent-encoded `filename-string` of RFC 5547
+pub fn decode_file_name(name: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(name.len());
+    let mut iter = name.iter();
+    while let Some(c) = iter.next() {
+        if *c == b'%' {
+            let high = hex_value(*iter.next()?)?;
+            let low = hex_value(*iter.next()?)?;
+            decoded.push(high << 4 | low);
+        } else {
+            decoded.push(*c);
+        }
+    }
+    Some(decoded)
+}
+
+/// Percent-encodes a file name so that it can be put in a `file-selector`
+pub fn encode_file_name(name: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(name.len());
+    for c in name {
+        match c {
+            0x00 | b'\r' | b'\n' | b'"' | b'%' | b' ' => {
+                encoded.extend_from_slice(format!("%{:02X}", c).as_bytes())
+            }
+            _ => encoded.push(*c),
+        }
+    }
+    encoded
+}
+
+/// `hash:<algorithm>:<hex pairs>` of a `file-selector`
+#[derive(Clone, PartialEq, Eq)]
+pub struct FileHash<'a> {
+    /// Hash function from the IANA textual names registry, `sha-1` for RCS
+    pub algorithm: &'a [u8],
+    pub digest: Vec<u8>,
+}
+
+impl<'a> fmt::Debug for FileHash<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digest = Vec::new();
+        write_hex_pairs(&mut digest, &self.digest).map_err(|_| fmt::Error)?;
+        f.debug_struct("FileHash")
+            .field("algorithm", &String::from_utf8_lossy(self.algorithm))
+            .field("digest", &String::from_utf8_lossy(&digest))
+            .finish()
+    }
+}
+
+/// Value of an `a=file-selector` line, every selector being optional
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct FileSelector<'a> {
+    /// File name as written between the quotes, still percent-encoded
+    pub name: Option<&'a [u8]>,
+    /// MIME type with optional parameters
+    pub file_type: Option<&'a [u8]>,
+    /// Size in octets
+    pub size: Option<u64>,
+    pub hash: Option<FileHash<'a>>,
+}
+
+impl<'a> FileSelector<'a> {
+    pub fn parse(value: &'a [u8]) -> Option<FileSelector<'a>> {
+        let mut file_selector = FileSelector::default();
+        for selector in split_selectors(value) {
+            if let Some(name) = selector.strip_prefix(b"name:") {
+                let name = unquote(name)?;
+                decode_file_name(name)?;
+                file_selector.name.replace(name);
+            } else if let Some(file_type) = selector.strip_prefix(b"type:") {
+                if !file_type.contains(&b'/') {
+                    return None;
+                }
+                file_selector.file_type.replace(file_type);
+            } else if let Some(size) = selector.strip_prefix(b"size:") {
+                file_selector.size.replace(parse_number(size)?);
+            } else if let Some(hash) = selector.strip_prefix(b"hash:") {
+                let idx = hash.iter().position(|c| *c == b':')?;
+                file_selector.hash.replace(FileHash {
+                    algorithm: &hash[..idx],
+                    digest: parse_hex_pairs(&hash[idx + 1..])?,
+                });
+            } else {
+                return None;
+            }
+        }
+        Some(file_selector)
+    }
+
+    /// The file name with percent-encoding removed
+    pub fn decoded_name(&self) -> Option<Vec<u8>> {
+        decode_file_name(self.name?)
+    }
+
+    /// Writes the `a=file-selector` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"a=file-selector:")?;
+        self.write_value_to(w)?;
+        w.write_all(b"\r\n")
+    }
+
+    /// Writes what follows `file-selector:`
+    pub fn write_value_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut separator: &[u8] = b"";
+        if let Some(name) = self.name {
+            w.write_all(separator)?;
+            w.write_all(b"name:\"")?;
+            w.write_all(name)?;
+            w.write_all(b"\"")?;
+            separator = b" ";
+        }
+        if let Some(file_type) = self.file_type {
+            w.write_all(separator)?;
+            w.write_all(b"type:")?;
+            w.write_all(file_type)?;
+            separator = b" ";
+        }
+        if let Some(size) = self.size {
+            w.write_all(separator)?;
+            write!(w, "size:{}", size)?;
+            separator = b" ";
+        }
+        if let Some(hash) = &self.hash {
+            w.write_all(separator)?;
+            w.write_all(b"hash:")?;
+            w.write_all(hash.algorithm)?;
+            w.write_all(b":")?;
+            write_hex_pairs(w, &hash.digest)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for FileSelector<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("FileSelector");
+        if let Some(name) = self.name {
+            debug_struct.field("name", &String::from_utf8_lossy(name));
+        }
+        if let Some(file_type) = self.file_type {
+            debug_struct.field("file_type", &String::from_utf8_lossy(file_type));
+        }
+        if let Some(size) = self.size {
+            debug_struct.field("size", &size);
+        }
+        if let Some(hash) = &self.hash {
+            debug_struct.field("hash", hash);
+        }
+        debug_struct.finish()
+    }
+}
+
+impl<'a> fmt::Display for FileSelector<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Value of an `a=file-date` line, dates are RFC 5322 `date-time` strings without quotes
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct FileDate<'a> {
+    pub creation: Option<&'a [u8]>,
+    pub modification: Option<&'a [u8]>,
+    pub read: Option<&'a [u8]>,
+}
+
+impl<'a> FileDate<'a> {
+    pub fn parse(value: &'a [u8]) -> Option<FileDate<'a>> {
+        let mut file_date = FileDate::default();
+        for date in split_selectors(value) {
+            if let Some(creation) = date.strip_prefix(b"creation:") {
+                file_date.creation.replace(unquote(creation)?);
+            } else if let Some(modification) = date.strip_prefix(b"modification:") {
+                file_date.modification.replace(unquote(modification)?);
+            } else if let Some(read) = date.strip_prefix(b"read:") {
+                file_date.read.replace(unquote(read)?);
+            } else {
+                return None;
+            }
+        }
+        if file_date.creation.is_none()
+            && file_date.modification.is_none()
+            && file_date.read.is_none()
+        {
+            return None;
+        }
+        Some(file_date)
+    }
+
+    /// Writes the `a=file-date` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"a=file-date:")?;
+        self.write_value_to(w)?;
+        w.write_all(b"\r\n")
+    }
+
+    /// Writes what follows `file-date:`
+    pub fn write_value_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut separator: &[u8] = b"";
+        for (name, date) in [
+            (&b"creation"[..], self.creation),
+            (b"modification", self.modification),
+            (b"read", self.read),
+        ] {
+            if let Some(date) = date {
+                w.write_all(separator)?;
+                w.write_all(name)?;
+                w.write_all(b":\"")?;
+                w.write_all(date)?;
+                w.write_all(b"\"")?;
+                separator = b" ";
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for FileDate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("FileDate");
+        if let Some(creation) = self.creation {
+            debug_struct.field("creation", &String::from_utf8_lossy(creation));
+        }
+        if let Some(modification) = self.modification {
+            debug_struct.field("modification", &String::from_utf8_lossy(modification));
+        }
+        if let Some(read) = self.read {
+            debug_struct.field("read", &String::from_utf8_lossy(read));
+        }
+        debug_struct.finish()
+    }
+}
+
+impl<'a> fmt::Display for FileDate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Value of an `a=file-range` line, offsets are 1-based and inclusive
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FileRange {
+    pub start: u64,
+    /// `None` when written as `*`, the end of the file
+    pub stop: Option<u64>,
+}
+
+impl FileRange {
+    pub fn parse(value: &[u8]) -> Option<FileRange> {
+        let idx = value.iter().position(|c| *c == b'-')?;
+        let start = parse_number(&value[..idx])?;
+        let stop = match &value[idx + 1..] {
+            b"*" => None,
+            stop => Some(parse_number(stop)?),
+        };
+        if start == 0 || stop.is_some_and(|stop| stop < start) {
+            return None;
+        }
+        Some(FileRange { start, stop })
+    }
+
+    /// Writes the `a=file-range` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"a=file-range:")?;
+        self.write_value_to(w)?;
+        w.write_all(b"\r\n")
+    }
+
+    /// Writes what follows `file-range:`
+    pub fn write_value_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self.stop {
+            Some(stop) => write!(w, "{}-{}", self.start, stop),
+            None => write!(w, "{}-*", self.start),
+        }
+    }
+}
+
+impl fmt::Display for FileRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// `a=file-transfer-id`, written with `Attribute::write_to()` or added with
+/// `MediaBuilder::with_attribute()`
+pub fn file_transfer_id_attribute(id: &[u8]) -> Attribute<'_> {
+    Attribute {
+        name: b"file-transfer-id",
+        value: Some(id),
+    }
+}
+
+/// `a=file-disposition`, `render`, `attachment` or another token
+pub fn file_disposition_attribute(disposition: &[u8]) -> Attribute<'_> {
+    Attribute {
+        name: b"file-disposition",
+        value: Some(disposition),
+    }
+}
+
+/// `a=file-icon`, `None` unless `cid` is a `cid:` URL
+pub fn file_icon_attribute(cid: &[u8]) -> Option<Attribute<'_>> {
+    if !cid.starts_with(b"cid:") {
+        return None;
+    }
+    Some(Attribute {
+        name: b"file-icon",
+        value: Some(cid),
+    })
+}
+
+impl<'a> Media<'a> {
+    /// `a=file-selector`, `None` when absent or malformed
+    pub fn file_selector(&self) -> Option<FileSelector<'a>> {
+        FileSelector::parse(self.attribute(b"file-selector")?.value?)
+    }
+
+    /// `a=file-transfer-id`
+    pub fn file_transfer_id(&self) -> Option<&'a [u8]> {
+        self.attribute(b"file-transfer-id")?
+            .value
+            .filter(|value| !value.is_empty())
+    }
+
+    /// `a=file-disposition`, `render` or `attachment`
+    pub fn file_disposition(&self) -> Option<&'a [u8]> {
+        self.attribute(b"file-disposition")?
+            .value
+            .filter(|value| !value.is_empty())
+    }
+
+    /// `a=file-date`, `None` when absent or malformed
+    pub fn file_date(&self) -> Option<FileDate<'a>> {
+        FileDate::parse(self.attribute(b"file-date")?.value?)
+    }
+
+    /// `a=file-icon`, a `cid:` URL of RFC 2392
+    pub fn file_icon(&self) -> Option<&'a [u8]> {
+        self.attribute(b"file-icon")?
+            .value
+            .filter(|value| value.starts_with(b"cid:"))
+    }
+
+    /// `a=file-range`, `None` when absent or malformed
+    pub fn file_range(&self) -> Option<FileRange> {
+        FileRange::parse(self.attribute(b"file-range")?.value?)
+    }
+}
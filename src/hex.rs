@@ -0,0 +1,47 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+
+pub(crate) fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        _ => None,
+    }
+}
+
+/// Parses `2HEX *(":" 2HEX)`
+pub(crate) fn parse_hex_pairs(value: &[u8]) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for pair in value.split(|c| *c == b':') {
+        match pair {
+            [high, low] => bytes.push(hex_value(*high)? << 4 | hex_value(*low)?),
+            _ => return None,
+        }
+    }
+    Some(bytes)
+}
+
+/// Writes `2UHEX *(":" 2UHEX)`
+pub(crate) fn write_hex_pairs<W: io::Write>(w: &mut W, bytes: &[u8]) -> io::Result<()> {
+    for (idx, byte) in bytes.iter().enumerate() {
+        if idx > 0 {
+            w.write_all(b":")?;
+        }
+        write!(w, "{:02X}", byte)?;
+    }
+    Ok(())
+}
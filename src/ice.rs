@@ -0,0 +1,328 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt, io, net::IpAddr};
+
+use crate::{parse_number, split_tokens, Attribute, Media, Sdp};
+
+fn parse_ip(address: &[u8]) -> Option<IpAddr> {
+    std::str::from_utf8(address).ok()?.parse().ok()
+}
+
+/// `typ` of a candidate
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandidateType<'a> {
+    Host,
+    Srflx,
+    Prflx,
+    Relay,
+    Other(&'a [u8]),
+}
+
+impl<'a> CandidateType<'a> {
+    pub fn from_bytes(value: &'a [u8]) -> CandidateType<'a> {
+        match value {
+            b"host" => CandidateType::Host,
+            b"srflx" => CandidateType::Srflx,
+            b"prflx" => CandidateType::Prflx,
+            b"relay" => CandidateType::Relay,
+            other => CandidateType::Other(other),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            CandidateType::Host => b"host",
+            CandidateType::Srflx => b"srflx",
+            CandidateType::Prflx => b"prflx",
+            CandidateType::Relay => b"relay",
+            CandidateType::Other(other) => other,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for CandidateType<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+/// Value of an `a=candidate` line, RFC 8839 section 5.1
+#[derive(Clone, PartialEq, Eq)]
+pub struct IceCandidate<'a> {
+    pub foundation: &'a [u8],
+    /// 1 to 256, 1 being RTP and 2 RTCP
+    pub component: u16,
+    /// `UDP`, or `TCP` for RFC 6544 candidates, kept as written
+    pub transport: &'a [u8],
+    pub priority: u32,
+    /// IP address or FQDN, kept as written, see `ip_address()`
+    pub address: &'a [u8],
+    pub port: u16,
+    pub typ: CandidateType<'a>,
+    pub raddr: Option<&'a [u8]>,
+    pub rport: Option<u16>,
+    /// Extension attributes as name/value pairs, `tcptype` or `generation` for example
+    pub extensions: Vec<(&'a [u8], &'a [u8])>,
+}
+
+impl<'a> IceCandidate<'a> {
+    pub fn parse(value: &'a [u8]) -> Option<IceCandidate<'a>> {
+        let mut iter = value.split(|c| *c == b' ');
+
+        let foundation = iter.next()?;
+        if foundation.is_empty() || foundation.len() > 32 {
+            return None;
+        }
+        let component = parse_number::<u16>(iter.next()?)?;
+        if !(1..=256).contains(&component) {
+            return None;
+        }
+        let transport = iter.next()?;
+        if transport.is_empty() {
+            return None;
+        }
+        let priority = parse_number::<u32>(iter.next()?)?;
+        let address = iter.next()?;
+        if address.is_empty() {
+            return None;
+        }
+        let port = parse_number::<u16>(iter.next()?)?;
+        if iter.next()? != b"typ" {
+            return None;
+        }
+        let typ = iter.next()?;
+        if typ.is_empty() {
+            return None;
+        }
+
+        let mut candidate = IceCandidate {
+            foundation,
+            component,
+            transport,
+            priority,
+            address,
+            port,
+            typ: CandidateType::from_bytes(typ),
+            raddr: None,
+            rport: None,
+            extensions: Vec::new(),
+        };
+
+        while let Some(name) = iter.next() {
+            let value = iter.next()?;
+            match name {
+                b"raddr" if candidate.raddr.is_none() && candidate.extensions.is_empty() => {
+                    candidate.raddr.replace(value);
+                }
+                b"rport" if candidate.rport.is_none() && candidate.extensions.is_empty() => {
+                    candidate.rport.replace(parse_number::<u16>(value)?);
+                }
+                _ if !name.is_empty() && !value.is_empty() => {
+                    candidate.extensions.push((name, value))
+                }
+                _ => return None,
+            }
+        }
+
+        Some(candidate)
+    }
+
+    /// The connection address as an IP, `None` for an FQDN
+    pub fn ip_address(&self) -> Option<IpAddr> {
+        parse_ip(self.address)
+    }
+
+    /// Writes the `a=candidate` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"a=candidate:")?;
+        self.write_value_to(w)?;
+        w.write_all(b"\r\n")
+    }
+
+    /// Writes what follows `candidate:`
+    pub fn write_value_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(self.foundation)?;
+        write!(w, " {} ", self.component)?;
+        w.write_all(self.transport)?;
+        write!(w, " {} ", self.priority)?;
+        w.write_all(self.address)?;
+        write!(w, " {} typ ", self.port)?;
+        w.write_all(self.typ.as_bytes())?;
+        if let Some(raddr) = self.raddr {
+            w.write_all(b" raddr ")?;
+            w.write_all(raddr)?;
+        }
+        if let Some(rport) = self.rport {
+            write!(w, " rport {}", rport)?;
+        }
+        for (name, value) in &self.extensions {
+            w.write_all(b" ")?;
+            w.write_all(name)?;
+            w.write_all(b" ")?;
+            w.write_all(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for IceCandidate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("IceCandidate");
+        debug_struct
+            .field("foundation", &String::from_utf8_lossy(self.foundation))
+            .field("component", &self.component)
+            .field("transport", &String::from_utf8_lossy(self.transport))
+            .field("priority", &self.priority)
+            .field("address", &String::from_utf8_lossy(self.address))
+            .field("port", &self.port)
+            .field("typ", &self.typ);
+        if let Some(raddr) = self.raddr {
+            debug_struct.field("raddr", &String::from_utf8_lossy(raddr));
+        }
+        if let Some(rport) = self.rport {
+            debug_struct.field("rport", &rport);
+        }
+        for (name, value) in &self.extensions {
+            debug_struct.field(
+                &String::from_utf8_lossy(name),
+                &String::from_utf8_lossy(value),
+            );
+        }
+        debug_struct.finish()
+    }
+}
+
+impl<'a> fmt::Display for IceCandidate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// One `<component> <address> <port>` entry of `a=remote-candidates`
+#[derive(Clone, PartialEq, Eq)]
+pub struct RemoteCandidate<'a> {
+    pub component: u16,
+    pub address: &'a [u8],
+    pub port: u16,
+}
+
+impl<'a> RemoteCandidate<'a> {
+    /// Parses the value of an `a=remote-candidates` line
+    pub fn parse_list(value: &'a [u8]) -> Option<Vec<RemoteCandidate<'a>>> {
+        let items: Vec<&[u8]> = value.split(|c| *c == b' ').collect();
+        if items.is_empty() || items.len() % 3 != 0 {
+            return None;
+        }
+        let mut remote_candidates = Vec::with_capacity(items.len() / 3);
+        for chunk in items.chunks(3) {
+            if chunk[1].is_empty() {
+                return None;
+            }
+            remote_candidates.push(RemoteCandidate {
+                component: parse_number::<u16>(chunk[0])?,
+                address: chunk[1],
+                port: parse_number::<u16>(chunk[2])?,
+            });
+        }
+        Some(remote_candidates)
+    }
+}
+
+impl<'a> fmt::Debug for RemoteCandidate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteCandidate")
+            .field("component", &self.component)
+            .field("address", &String::from_utf8_lossy(self.address))
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+pub(crate) fn candidates_of<'a>(attributes: &[Attribute<'a>]) -> Vec<IceCandidate<'a>> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.name == b"candidate")
+        .filter_map(|attribute| IceCandidate::parse(attribute.value?))
+        .collect()
+}
+
+fn value_of<'a>(attributes: &[Attribute<'a>], name: &[u8]) -> Option<&'a [u8]> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == name)?
+        .value
+        .filter(|value| !value.is_empty())
+}
+
+impl<'a> Sdp<'a> {
+    /// Session level `a=ice-ufrag`
+    pub fn ice_ufrag(&self) -> Option<&'a [u8]> {
+        value_of(&self.attributes, b"ice-ufrag")
+    }
+
+    /// Session level `a=ice-pwd`
+    pub fn ice_pwd(&self) -> Option<&'a [u8]> {
+        value_of(&self.attributes, b"ice-pwd")
+    }
+
+    /// Session level `a=ice-options` tokens
+    pub fn ice_options(&self) -> Vec<&'a [u8]> {
+        value_of(&self.attributes, b"ice-options")
+            .map(split_tokens)
+            .unwrap_or_default()
+    }
+
+    /// Whether `a=ice-lite` is present, it is only allowed at session level
+    pub fn is_ice_lite(&self) -> bool {
+        self.has_flag(b"ice-lite")
+    }
+}
+
+impl<'a> Media<'a> {
+    /// Well-formed `a=candidate` attributes in order of appearance
+    pub fn candidates(&self) -> Vec<IceCandidate<'a>> {
+        candidates_of(&self.attributes)
+    }
+
+    /// `a=ice-ufrag` of this media, falling back to the session level one
+    pub fn ice_ufrag(&self, sdp: &Sdp<'a>) -> Option<&'a [u8]> {
+        value_of(&self.attributes, b"ice-ufrag").or_else(|| sdp.ice_ufrag())
+    }
+
+    /// `a=ice-pwd` of this media, falling back to the session level one
+    pub fn ice_pwd(&self, sdp: &Sdp<'a>) -> Option<&'a [u8]> {
+        value_of(&self.attributes, b"ice-pwd").or_else(|| sdp.ice_pwd())
+    }
+
+    /// `a=ice-options` tokens of this media, falling back to the session level ones
+    pub fn ice_options(&self, sdp: &Sdp<'a>) -> Vec<&'a [u8]> {
+        match value_of(&self.attributes, b"ice-options") {
+            Some(value) => split_tokens(value),
+            None => sdp.ice_options(),
+        }
+    }
+
+    /// Whether `a=end-of-candidates` is present at media or session level
+    pub fn is_end_of_candidates(&self, sdp: &Sdp) -> bool {
+        self.has_flag(b"end-of-candidates") || sdp.has_flag(b"end-of-candidates")
+    }
+
+    /// `a=remote-candidates`, `None` when absent or malformed
+    pub fn remote_candidates(&self) -> Option<Vec<RemoteCandidate<'a>>> {
+        RemoteCandidate::parse_list(value_of(&self.attributes, b"remote-candidates")?)
+    }
+}
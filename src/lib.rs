@@ -12,8 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{fmt, str::FromStr};
-
+use std::{fmt, io, str::FromStr};
+
+use time::{RepeatTime, TimeDescription, TimeZoneAdjustment};
+
+mod base64;
+mod builder;
+pub mod direction;
+pub mod dtls;
+pub mod file_transfer;
+mod hex;
+pub mod ice;
+pub mod msrp;
+pub mod rtp;
+pub mod sdes;
+pub mod setup;
+pub mod time;
+pub mod trickle;
+
+pub use builder::{MediaBuilder, SdpBuilder};
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Origin<'a> {
     pub user_id: &'a [u8],
     pub session_id: &'a [u8],
@@ -26,22 +45,50 @@ pub struct Origin<'a> {
 impl<'a> fmt::Debug for Origin<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Origin")
-            .field("user_id", &String::from_utf8_lossy(&self.user_id))
-            .field("session_id", &String::from_utf8_lossy(&self.session_id))
+            .field("user_id", &String::from_utf8_lossy(self.user_id))
+            .field("session_id", &String::from_utf8_lossy(self.session_id))
             .field(
                 "session_version",
-                &String::from_utf8_lossy(&self.session_version),
+                &String::from_utf8_lossy(self.session_version),
             )
-            .field("network_type", &String::from_utf8_lossy(&self.network_type))
-            .field("address_type", &String::from_utf8_lossy(&self.address_type))
+            .field("network_type", &String::from_utf8_lossy(self.network_type))
+            .field("address_type", &String::from_utf8_lossy(self.address_type))
             .field(
                 "unicast_address",
-                &String::from_utf8_lossy(&self.unicast_address),
+                &String::from_utf8_lossy(self.unicast_address),
             )
             .finish()
     }
 }
 
+impl<'a> Origin<'a> {
+    /// Writes the `o=` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"o=")?;
+        w.write_all(self.user_id)?;
+        w.write_all(b" ")?;
+        w.write_all(self.session_id)?;
+        w.write_all(b" ")?;
+        w.write_all(self.session_version)?;
+        w.write_all(b" ")?;
+        w.write_all(self.network_type)?;
+        w.write_all(b" ")?;
+        w.write_all(self.address_type)?;
+        w.write_all(b" ")?;
+        w.write_all(self.unicast_address)?;
+        w.write_all(b"\r\n")
+    }
+}
+
+impl<'a> fmt::Display for Origin<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct ConnectionData<'a> {
     pub network_type: &'a [u8],
     pub address_type: &'a [u8],
@@ -51,58 +98,667 @@ pub struct ConnectionData<'a> {
 impl<'a> fmt::Debug for ConnectionData<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ConnectionData")
-            .field("network_type", &String::from_utf8_lossy(&self.network_type))
-            .field("address_type", &String::from_utf8_lossy(&self.address_type))
+            .field("network_type", &String::from_utf8_lossy(self.network_type))
+            .field("address_type", &String::from_utf8_lossy(self.address_type))
             .field(
                 "connection_address",
-                &String::from_utf8_lossy(&self.connection_address),
+                &String::from_utf8_lossy(self.connection_address),
             )
             .finish()
     }
 }
 
+impl<'a> ConnectionData<'a> {
+    /// Writes the `c=` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"c=")?;
+        w.write_all(self.network_type)?;
+        w.write_all(b" ")?;
+        w.write_all(self.address_type)?;
+        w.write_all(b" ")?;
+        w.write_all(self.connection_address)?;
+        w.write_all(b"\r\n")
+    }
+}
+
+impl<'a> fmt::Display for ConnectionData<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Value of an `e=` or `p=` line.
+///
+/// Both `j.doe@example.com (Jane Doe)` and `Jane Doe <j.doe@example.com>`
+/// forms are understood.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Contact<'a> {
+    pub address: &'a [u8],
+    pub display_name: Option<&'a [u8]>,
+}
+
+impl<'a> Contact<'a> {
+    fn parse(value: &'a [u8]) -> Contact<'a> {
+        let value = value.trim_ascii();
+
+        if let Some(stripped) = value.strip_suffix(b")") {
+            if let Some(idx) = stripped.iter().position(|c| *c == b'(') {
+                let address = stripped[..idx].trim_ascii();
+                if !address.is_empty() {
+                    return Contact {
+                        address,
+                        display_name: Some(stripped[idx + 1..].trim_ascii()),
+                    };
+                }
+            }
+        } else if let Some(stripped) = value.strip_suffix(b">") {
+            if let Some(idx) = stripped.iter().rposition(|c| *c == b'<') {
+                let display_name = stripped[..idx].trim_ascii();
+                return Contact {
+                    address: stripped[idx + 1..].trim_ascii(),
+                    display_name: if display_name.is_empty() {
+                        None
+                    } else {
+                        Some(display_name)
+                    },
+                };
+            }
+        }
+
+        Contact {
+            address: value,
+            display_name: None,
+        }
+    }
+
+    /// Writes the value in `address (display name)` form, without type letter
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(self.address)?;
+        if let Some(display_name) = self.display_name {
+            w.write_all(b" (")?;
+            w.write_all(display_name)?;
+            w.write_all(b")")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for Contact<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Contact")
+            .field("address", &String::from_utf8_lossy(self.address))
+            .field(
+                "display_name",
+                &self
+                    .display_name
+                    .map(|display_name| String::from_utf8_lossy(display_name)),
+            )
+            .finish()
+    }
+}
+
+impl<'a> fmt::Display for Contact<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum BandwidthModifier<'a> {
+    /// Conference Total, kbps
+    CT,
+    /// Application Specific, kbps
+    AS,
+    /// Transport Independent Application Specific, bps (RFC 3890)
+    TIAS,
+    /// RTCP bandwidth allocated to active senders, bps (RFC 3556)
+    RS,
+    /// RTCP bandwidth allocated to other participants, bps (RFC 3556)
+    RR,
+    /// `X-` prefixed or unknown modifiers, kept as-is
+    Other(&'a [u8]),
+}
+
+impl<'a> BandwidthModifier<'a> {
+    fn from_bytes(modifier: &'a [u8]) -> BandwidthModifier<'a> {
+        if modifier.eq_ignore_ascii_case(b"CT") {
+            BandwidthModifier::CT
+        } else if modifier.eq_ignore_ascii_case(b"AS") {
+            BandwidthModifier::AS
+        } else if modifier.eq_ignore_ascii_case(b"TIAS") {
+            BandwidthModifier::TIAS
+        } else if modifier.eq_ignore_ascii_case(b"RS") {
+            BandwidthModifier::RS
+        } else if modifier.eq_ignore_ascii_case(b"RR") {
+            BandwidthModifier::RR
+        } else {
+            BandwidthModifier::Other(modifier)
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            BandwidthModifier::CT => b"CT",
+            BandwidthModifier::AS => b"AS",
+            BandwidthModifier::TIAS => b"TIAS",
+            BandwidthModifier::RS => b"RS",
+            BandwidthModifier::RR => b"RR",
+            BandwidthModifier::Other(modifier) => modifier,
+        }
+    }
+
+    /// CT, AS, RS and RR values fit in a u32, TIAS and unknown modifiers in a u64
+    pub(crate) fn max_value(&self) -> u64 {
+        match self {
+            BandwidthModifier::CT
+            | BandwidthModifier::AS
+            | BandwidthModifier::RS
+            | BandwidthModifier::RR => u64::from(u32::MAX),
+            BandwidthModifier::TIAS | BandwidthModifier::Other(_) => u64::MAX,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for BandwidthModifier<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+/// Value of a `b=` line
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Bandwidth<'a> {
+    pub modifier: BandwidthModifier<'a>,
+    /// CT, AS, RS and RR values fit in a u32, TIAS and unknown modifiers in a u64
+    pub value: u64,
+}
+
+impl<'a> Bandwidth<'a> {
+    fn parse(value: &'a [u8]) -> Option<Bandwidth<'a>> {
+        let idx = value.iter().position(|c| *c == b':')?;
+        let modifier = BandwidthModifier::from_bytes(&value[..idx]);
+        let value = &value[idx + 1..];
+        if modifier.as_bytes().is_empty() || !value.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let value = value.to_int::<u64>().ok()?;
+        if value > modifier.max_value() {
+            return None;
+        }
+        Some(Bandwidth { modifier, value })
+    }
+
+    /// Writes the `b=` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"b=")?;
+        w.write_all(self.modifier.as_bytes())?;
+        write!(w, ":{}\r\n", self.value)
+    }
+}
+
+impl<'a> fmt::Display for Bandwidth<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Value of a `k=` line.
+///
+/// RFC 8866 obsoletes `k=` altogether (section 5.12), it is only kept for
+/// RFC 4566 peers, see `is_deprecated()`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum KeyMethod<'a> {
+    Clear(&'a [u8]),
+    /// Still base64 encoded, see `decoded_key()`
+    Base64(&'a [u8]),
+    Uri(&'a [u8]),
+    Prompt,
+    /// Extension methods, the whole value as written
+    Other(&'a [u8]),
+}
+
+impl<'a> KeyMethod<'a> {
+    fn parse(value: &'a [u8]) -> Option<KeyMethod<'a>> {
+        if value == b"prompt" {
+            return Some(KeyMethod::Prompt);
+        }
+
+        let (method, key) = match value.iter().position(|c| *c == b':') {
+            Some(idx) => (&value[..idx], Some(&value[idx + 1..])),
+            None => (value, None),
+        };
+        match (method, key) {
+            (b"clear", Some(key)) => Some(KeyMethod::Clear(key)),
+            (b"base64", Some(key)) => {
+                base64::decode(key)?;
+                Some(KeyMethod::Base64(key))
+            }
+            (b"uri", Some(key)) => Some(KeyMethod::Uri(key)),
+            (b"clear" | b"base64" | b"uri" | b"prompt", _) | (b"", _) => None,
+            _ => Some(KeyMethod::Other(value)),
+        }
+    }
+
+    /// The key itself for `clear` and `base64` methods
+    pub fn decoded_key(&self) -> Option<Vec<u8>> {
+        match self {
+            KeyMethod::Clear(key) => Some(key.to_vec()),
+            KeyMethod::Base64(key) => base64::decode(key),
+            KeyMethod::Uri(_) | KeyMethod::Prompt | KeyMethod::Other(_) => None,
+        }
+    }
+
+    /// Always true, no key method survives in RFC 8866 (section 5.12)
+    pub fn is_deprecated(&self) -> bool {
+        true
+    }
+
+    /// Writes the `k=` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            KeyMethod::Clear(key) => {
+                w.write_all(b"k=clear:")?;
+                w.write_all(key)?;
+            }
+            KeyMethod::Base64(key) => {
+                w.write_all(b"k=base64:")?;
+                w.write_all(key)?;
+            }
+            KeyMethod::Uri(uri) => {
+                w.write_all(b"k=uri:")?;
+                w.write_all(uri)?;
+            }
+            KeyMethod::Prompt => {
+                w.write_all(b"k=prompt")?;
+            }
+            KeyMethod::Other(value) => {
+                w.write_all(b"k=")?;
+                w.write_all(value)?;
+            }
+        }
+        w.write_all(b"\r\n")
+    }
+}
+
+impl<'a> fmt::Debug for KeyMethod<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyMethod::Clear(key) => f
+                .debug_tuple("Clear")
+                .field(&String::from_utf8_lossy(key))
+                .finish(),
+            KeyMethod::Base64(key) => f
+                .debug_tuple("Base64")
+                .field(&String::from_utf8_lossy(key))
+                .finish(),
+            KeyMethod::Uri(uri) => f
+                .debug_tuple("Uri")
+                .field(&String::from_utf8_lossy(uri))
+                .finish(),
+            KeyMethod::Prompt => f.write_str("Prompt"),
+            KeyMethod::Other(value) => f
+                .debug_tuple("Other")
+                .field(&String::from_utf8_lossy(value))
+                .finish(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for KeyMethod<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Value of an `a=` line.
+///
+/// Property attributes such as `a=recvonly` have no value, value
+/// attributes such as `a=rtpmap:0 PCMU/8000` are split at the first `:`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Attribute<'a> {
+    pub name: &'a [u8],
+    pub value: Option<&'a [u8]>,
+}
+
+impl<'a> Attribute<'a> {
+    pub fn parse(attribute: &'a [u8]) -> Attribute<'a> {
+        match attribute.iter().position(|c| *c == b':') {
+            Some(idx) => Attribute {
+                name: &attribute[..idx],
+                value: Some(&attribute[idx + 1..]),
+            },
+            None => Attribute {
+                name: attribute,
+                value: None,
+            },
+        }
+    }
+
+    /// Writes the `a=` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"a=")?;
+        w.write_all(self.name)?;
+        if let Some(value) = self.value {
+            w.write_all(b":")?;
+            w.write_all(value)?;
+        }
+        w.write_all(b"\r\n")
+    }
+}
+
+impl<'a> fmt::Debug for Attribute<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Some(value) => write!(
+                f,
+                "{}:{}",
+                String::from_utf8_lossy(self.name),
+                String::from_utf8_lossy(value)
+            ),
+            None => f.write_str(&String::from_utf8_lossy(self.name)),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Attribute<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+fn find_attribute<'s, 'a>(
+    attributes: &'s [Attribute<'a>],
+    name: &[u8],
+) -> Option<&'s Attribute<'a>> {
+    attributes.iter().find(|attribute| attribute.name == name)
+}
+
+fn find_flag(attributes: &[Attribute], name: &[u8]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| attribute.name == name && attribute.value.is_none())
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Media<'a> {
     pub media_type: &'a [u8],
     pub port: u16,
     pub number_of_ports: i32,
     pub protocol: &'a [u8],
     pub formats: Vec<&'a [u8]>,
+    pub title: Option<&'a [u8]>,
     pub connection: Option<ConnectionData<'a>>,
-    pub attributes: Vec<&'a [u8]>,
+    pub bandwidths: Vec<Bandwidth<'a>>,
+    pub encryption_key: Option<KeyMethod<'a>>,
+    pub attributes: Vec<Attribute<'a>>,
 }
 
 impl<'a> fmt::Debug for Media<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut debug_struct = f.debug_struct("Media");
         debug_struct
-            .field("media_type", &String::from_utf8_lossy(&self.media_type))
+            .field("media_type", &String::from_utf8_lossy(self.media_type))
             .field("port", &self.port)
             .field("number_of_ports", &self.number_of_ports)
-            .field("protocol", &String::from_utf8_lossy(&self.protocol));
+            .field("protocol", &String::from_utf8_lossy(self.protocol));
         for format in &self.formats {
             debug_struct.field("format", &String::from_utf8_lossy(format));
         }
+        if let Some(title) = self.title {
+            debug_struct.field("title", &String::from_utf8_lossy(title));
+        }
         if let Some(connection) = &self.connection {
             debug_struct.field("connection", connection);
         }
+        for bandwidth in &self.bandwidths {
+            debug_struct.field("bandwidth", bandwidth);
+        }
+        if let Some(encryption_key) = &self.encryption_key {
+            debug_struct.field("encryption_key", encryption_key);
+        }
         for attribute in &self.attributes {
-            debug_struct.field("attribute", &String::from_utf8_lossy(attribute));
+            debug_struct.field("attribute", attribute);
         }
         debug_struct.finish()
     }
 }
 
+impl<'a> Media<'a> {
+    /// First media level attribute called `name`
+    pub fn attribute(&self, name: &[u8]) -> Option<&Attribute<'a>> {
+        find_attribute(&self.attributes, name)
+    }
+
+    /// All media level attributes called `name`, in order of appearance
+    pub fn attributes_named<'s>(
+        &'s self,
+        name: &'s [u8],
+    ) -> impl Iterator<Item = &'s Attribute<'a>> + 's {
+        self.attributes
+            .iter()
+            .filter(move |attribute| attribute.name == name)
+    }
+
+    /// Whether a media level property attribute called `name` is present
+    pub fn has_flag(&self, name: &[u8]) -> bool {
+        find_flag(&self.attributes, name)
+    }
+
+    /// `a=mid` identification tag of RFC 5888
+    pub fn mid(&self) -> Option<&'a [u8]> {
+        self.attribute(b"mid")?.value
+    }
+
+    /// Writes the `m=` line followed by the lines of the media description
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"m=")?;
+        w.write_all(self.media_type)?;
+        if self.number_of_ports == 1 {
+            write!(w, " {} ", self.port)?;
+        } else {
+            write!(w, " {}/{} ", self.port, self.number_of_ports)?;
+        }
+        w.write_all(self.protocol)?;
+        for format in &self.formats {
+            w.write_all(b" ")?;
+            w.write_all(format)?;
+        }
+        w.write_all(b"\r\n")?;
+
+        if let Some(title) = self.title {
+            w.write_all(b"i=")?;
+            w.write_all(title)?;
+            w.write_all(b"\r\n")?;
+        }
+
+        if let Some(connection) = &self.connection {
+            connection.write_to(w)?;
+        }
+
+        for bandwidth in &self.bandwidths {
+            bandwidth.write_to(w)?;
+        }
+
+        if let Some(encryption_key) = &self.encryption_key {
+            encryption_key.write_to(w)?;
+        }
+
+        for attribute in &self.attributes {
+            attribute.write_to(w)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for Media<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Sdp<'a> {
     pub version: &'a [u8],
     pub origin: Origin<'a>,
     pub session_name: &'a [u8],
+    pub information: Option<&'a [u8]>,
+    pub uri: Option<&'a [u8]>,
+    pub emails: Vec<Contact<'a>>,
+    pub phones: Vec<Contact<'a>>,
     pub connection: Option<ConnectionData<'a>>,
-    pub session_start_time: u64,
-    pub session_end_time: u64,
-    pub attributes: Vec<&'a [u8]>,
+    pub bandwidths: Vec<Bandwidth<'a>>,
+    pub times: Vec<TimeDescription>,
+    pub time_zone_adjustments: Vec<TimeZoneAdjustment>,
+    pub encryption_key: Option<KeyMethod<'a>>,
+    pub attributes: Vec<Attribute<'a>>,
     pub medias: Vec<Media<'a>>,
 }
 
+/// Where in the input a parse error was detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLocation {
+    /// 1-based line number
+    pub line_number: usize,
+    /// Byte offset of the start of the line
+    pub offset: usize,
+    /// The offending line, without its line terminator
+    pub line: String,
+}
+
+impl ErrorLocation {
+    fn locate(buf: &[u8], offset: usize) -> ErrorLocation {
+        let mut line_number = 1;
+        let mut i = 0;
+        while i < offset {
+            if buf[i] == b'\n' || (buf[i] == b'\r' && buf.get(i + 1) != Some(&b'\n')) {
+                line_number += 1;
+            }
+            i += 1;
+        }
+
+        let end = buf[offset..]
+            .iter()
+            .position(|c| *c == b'\r' || *c == b'\n')
+            .map_or(buf.len(), |idx| offset + idx);
+
+        ErrorLocation {
+            line_number,
+            offset,
+            line: String::from_utf8_lossy(&buf[offset..end]).into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} (offset {}): {}",
+            self.line_number, self.offset, self.line
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SdpError {
+    BadVersion(ErrorLocation),
+    BadOrigin(ErrorLocation),
+    BadSessionName(ErrorLocation),
+    BadConnection(ErrorLocation),
+    BadMediaPort(ErrorLocation),
+    BadBandwidth(ErrorLocation),
+    BadTime(ErrorLocation),
+    /// Time values that overflow, or a stop time earlier than the start time
+    TimeOutOfRange(ErrorLocation),
+    BadRepeat(ErrorLocation),
+    BadTimeZone(ErrorLocation),
+    BadKey(ErrorLocation),
+    Duplicated(ErrorLocation),
+    OutOfOrder(ErrorLocation),
+    /// A line that is not `<type>=<value>`
+    BadLine(ErrorLocation),
+    /// An `m=` line cut short before its protocol or first format
+    BadMedia(ErrorLocation),
+    /// A mandatory field is absent or incomplete, identified by its type letter
+    MissingField(char),
+    /// A value given to the builder is out of range, identified by its type letter
+    InvalidField(char),
+}
+
+impl SdpError {
+    pub fn location(&self) -> Option<&ErrorLocation> {
+        match self {
+            SdpError::BadVersion(location)
+            | SdpError::BadOrigin(location)
+            | SdpError::BadSessionName(location)
+            | SdpError::BadConnection(location)
+            | SdpError::BadMediaPort(location)
+            | SdpError::BadBandwidth(location)
+            | SdpError::BadTime(location)
+            | SdpError::TimeOutOfRange(location)
+            | SdpError::BadRepeat(location)
+            | SdpError::BadTimeZone(location)
+            | SdpError::BadKey(location)
+            | SdpError::Duplicated(location)
+            | SdpError::OutOfOrder(location)
+            | SdpError::BadLine(location)
+            | SdpError::BadMedia(location) => Some(location),
+            SdpError::MissingField(_) | SdpError::InvalidField(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for SdpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SdpError::BadVersion(location) => write!(f, "Bad protocol version at {}", location),
+            SdpError::BadOrigin(location) => {
+                write!(f, "Bad originator and session identifier at {}", location)
+            }
+            SdpError::BadSessionName(location) => write!(f, "Bad session name at {}", location),
+            SdpError::BadConnection(location) => {
+                write!(f, "Bad connection information at {}", location)
+            }
+            SdpError::BadMediaPort(location) => {
+                write!(f, "Bad media port format at {}", location)
+            }
+            SdpError::BadBandwidth(location) => write!(f, "Bad bandwidth at {}", location),
+            SdpError::BadTime(location) => {
+                write!(f, "Bad time description format at {}", location)
+            }
+            SdpError::TimeOutOfRange(location) => {
+                write!(f, "Time out of range at {}", location)
+            }
+            SdpError::BadRepeat(location) => write!(f, "Bad repeat time at {}", location),
+            SdpError::BadTimeZone(location) => {
+                write!(f, "Bad time zone adjustment at {}", location)
+            }
+            SdpError::BadKey(location) => write!(f, "Bad encryption key at {}", location),
+            SdpError::Duplicated(location) => write!(f, "Duplicated description at {}", location),
+            SdpError::OutOfOrder(location) => write!(f, "Out of order description at {}", location),
+            SdpError::BadLine(location) => write!(f, "Malformed line at {}", location),
+            SdpError::BadMedia(location) => write!(f, "Bad media description at {}", location),
+            SdpError::MissingField(field) => write!(f, "Missing mandatory field {}=", field),
+            SdpError::InvalidField(field) => write!(f, "Invalid value for field {}=", field),
+        }
+    }
+}
+
+impl std::error::Error for SdpError {}
+
 #[derive(Debug)]
 enum Phase {
     Begin,
@@ -112,6 +768,7 @@ enum Phase {
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 enum Section<'a> {
     Main,
     Time,
@@ -120,8 +777,11 @@ enum Section<'a> {
         Option<(u16, i32)>,
         Option<&'a [u8]>,
         Option<Vec<&'a [u8]>>,
+        Option<&'a [u8]>,
         Option<ConnectionData<'a>>,
-        Option<Vec<&'a [u8]>>,
+        Vec<Bandwidth<'a>>,
+        Option<KeyMethod<'a>>,
+        Vec<Attribute<'a>>,
     ),
 }
 
@@ -176,46 +836,60 @@ impl<'a> Operator<'a> {
             Operator::M => 15,
         }
     }
+
+    fn is_repeatable(&self) -> bool {
+        matches!(self, Operator::E | Operator::P | Operator::B | Operator::A)
+    }
 }
 
 pub trait AsSDP<'a> {
     type Target;
-    fn as_sdp(&'a self) -> Option<Self::Target>;
+    fn as_sdp(&'a self) -> Result<Self::Target, SdpError>;
 }
 
 impl<'a> AsSDP<'a> for [u8] {
     type Target = Sdp<'a>;
-    fn as_sdp(&self) -> Option<Sdp> {
+    fn as_sdp(&'a self) -> Result<Sdp<'a>, SdpError> {
         let mut phase = Phase::Begin;
         let mut section = Section::Main;
         let mut op: Operator = Operator::None;
+        // Type of the line being started, `op` only takes it once `=` is seen
+        let mut next_op: Operator = Operator::None;
 
         let mut slice_start: Option<usize> = None;
+        let mut line_start: usize = 0;
 
         let mut version: Option<&[u8]> = None;
         let mut origin: Option<Origin> = None;
         let mut session_name: Option<&[u8]> = None;
+        let mut information: Option<&[u8]> = None;
+        let mut uri: Option<&[u8]> = None;
+        let mut emails: Vec<Contact> = Vec::new();
+        let mut phones: Vec<Contact> = Vec::new();
         let mut connection: Option<ConnectionData> = None;
+        let mut bandwidths: Vec<Bandwidth> = Vec::new();
 
-        let mut session_start_time: u64 = 0;
-        let mut session_end_time: u64 = 0;
+        let mut times: Vec<TimeDescription> = Vec::new();
+        let mut time_zone_adjustments: Vec<TimeZoneAdjustment> = Vec::new();
+        let mut encryption_key: Option<KeyMethod> = None;
 
         let mut medias: Vec<Media> = Vec::new();
 
-        let mut attributes: Vec<&[u8]> = Vec::new();
+        let mut attributes: Vec<Attribute> = Vec::new();
 
         let mut i = 0;
 
-        while i < self.len() {
-            let b = self[i];
+        // The end of the input terminates the last line like a line break
+        while i <= self.len() {
+            let b = self.get(i).copied().unwrap_or(b'\n');
 
             match &phase {
                 Phase::Begin => {
                     if b == b'\r' || b == b'\n' {
-                        i = i + 1;
+                        i += 1;
                         continue;
                     } else {
-                        let mut next_op: Operator = Operator::None;
+                        line_start = i;
 
                         if b == b'v' {
                             next_op = Operator::V;
@@ -249,52 +923,7 @@ impl<'a> AsSDP<'a> for [u8] {
                             next_op = Operator::M;
                         }
 
-                        match &section {
-                            Section::Main => {
-                                if op.get_order() < next_op.get_order() {
-                                    op = next_op;
-                                    phase = Phase::Set;
-                                } else if let (Operator::A, Operator::A) = (&op, &next_op) {
-                                    op = next_op;
-                                    phase = Phase::Set;
-                                } else {
-                                    phase = Phase::SkippingError;
-                                }
-                            }
-
-                            Section::Time => {
-                                if next_op.get_order() >= Operator::T(None).get_order() {
-                                    op = next_op;
-                                    phase = Phase::Set;
-                                } else {
-                                    phase = Phase::SkippingError;
-                                }
-                            }
-
-                            Section::Media(_, _, _, _, _, _) => {
-                                if op.get_order() < next_op.get_order() {
-                                    match next_op {
-                                        Operator::I
-                                        | Operator::C(_, _)
-                                        | Operator::B
-                                        | Operator::K
-                                        | Operator::A
-                                        | Operator::M => {
-                                            op = next_op;
-                                            phase = Phase::Set;
-                                        }
-                                        _ => {
-                                            phase = Phase::SkippingError;
-                                        }
-                                    }
-                                } else if let (Operator::A, Operator::A) = (&op, &next_op) {
-                                    op = next_op;
-                                    phase = Phase::Set;
-                                } else {
-                                    phase = Phase::SkippingError;
-                                }
-                            }
-                        }
+                        phase = Phase::Set;
                     }
                 }
 
@@ -345,22 +974,54 @@ impl<'a> AsSDP<'a> for [u8] {
                                         }
 
                                         _ => {
-                                            println!(
-                                                "Incomplete originator and session identifier",
-                                            );
-                                            return None;
+                                            return Err(SdpError::BadOrigin(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
                                         }
                                     }
                                 }
 
                                 Operator::S => {
+                                    match slice_start {
+                                        Some(slice_start) => {
+                                            session_name.replace(&self[slice_start..i]);
+                                        }
+                                        None => {
+                                            return Err(SdpError::BadSessionName(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::I => {
+                                    if let Some(slice_start) = slice_start {
+                                        information.replace(&self[slice_start..i]);
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::U => {
                                     if let Some(slice_start) = slice_start {
-                                        session_name.replace(&self[slice_start..i]);
+                                        uri.replace(&self[slice_start..i]);
                                     }
                                     slice_start = None;
                                 }
 
-                                Operator::I | Operator::U | Operator::E | Operator::P => {}
+                                Operator::E => {
+                                    if let Some(slice_start) = slice_start {
+                                        emails.push(Contact::parse(&self[slice_start..i]));
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::P => {
+                                    if let Some(slice_start) = slice_start {
+                                        phones.push(Contact::parse(&self[slice_start..i]));
+                                    }
+                                    slice_start = None;
+                                }
 
                                 Operator::C(network_type, address_type) => {
                                     match (network_type, address_type) {
@@ -377,94 +1038,204 @@ impl<'a> AsSDP<'a> for [u8] {
                                         }
 
                                         _ => {
-                                            println!("Incomplete connection information",);
-                                            return None;
+                                            return Err(SdpError::BadConnection(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
                                         }
                                     }
                                 }
 
-                                Operator::B | Operator::Z | Operator::K => {}
+                                Operator::B => {
+                                    if let Some(slice_start) = slice_start {
+                                        match Bandwidth::parse(&self[slice_start..i]) {
+                                            Some(bandwidth) => bandwidths.push(bandwidth),
+                                            None => {
+                                                return Err(SdpError::BadBandwidth(
+                                                    ErrorLocation::locate(self, line_start),
+                                                ));
+                                            }
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::Z => {
+                                    match slice_start.and_then(|slice_start| {
+                                        TimeZoneAdjustment::parse_list(&self[slice_start..i])
+                                    }) {
+                                        Some(adjustments) => time_zone_adjustments = adjustments,
+                                        None => {
+                                            return Err(SdpError::BadTimeZone(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::K => {
+                                    match slice_start.and_then(|slice_start| {
+                                        KeyMethod::parse(&self[slice_start..i])
+                                    }) {
+                                        Some(key_method) => {
+                                            encryption_key.replace(key_method);
+                                        }
+                                        None => {
+                                            return Err(SdpError::BadKey(ErrorLocation::locate(
+                                                self, line_start,
+                                            )));
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
 
                                 Operator::A => {
                                     if let Some(slice_start) = slice_start {
-                                        attributes.push(&self[slice_start..i]);
+                                        attributes.push(Attribute::parse(&self[slice_start..i]));
                                     }
                                     slice_start = None;
                                 }
 
                                 _ => {
-                                    println!("Unknown description in main section");
-                                    return None;
+                                    return Err(SdpError::OutOfOrder(ErrorLocation::locate(
+                                        self, line_start,
+                                    )));
                                 }
                             },
 
                             Section::Time => match &op {
                                 Operator::T(start_time) => {
-                                    if let Some(start_time) = start_time {
-                                        if start_time == b"0" {
-                                            session_start_time = u64::MIN;
-                                        } else {
-                                            if let Ok(t) = start_time.to_int::<u64>() {
-                                                session_start_time = t - 2208988800;
-                                            } else {
-                                                println!("Bad time description format",);
-                                                return None;
-                                            }
-                                        }
-                                        if let Some(slice_start) = slice_start {
-                                            let slice = &self[slice_start..i];
-                                            if slice == b"0" {
-                                                session_end_time = u64::MAX;
-                                            } else {
-                                                if let Ok(t) = slice.to_int::<u64>() {
-                                                    session_end_time = t - 2208988800;
-                                                } else {
-                                                    println!("Bad time description format",);
-                                                    return None;
+                                    match (start_time, slice_start) {
+                                        (Some(start_time), Some(slice_start)) => {
+                                            match TimeDescription::parse(
+                                                start_time,
+                                                &self[slice_start..i],
+                                            ) {
+                                                Some(Ok(time_description)) => {
+                                                    times.push(time_description)
+                                                }
+                                                Some(Err(_)) => {
+                                                    return Err(SdpError::TimeOutOfRange(
+                                                        ErrorLocation::locate(self, line_start),
+                                                    ));
+                                                }
+                                                None => {
+                                                    return Err(SdpError::BadTime(
+                                                        ErrorLocation::locate(self, line_start),
+                                                    ));
                                                 }
                                             }
-                                        } else {
-                                            println!("Bad time description format");
-                                            return None;
                                         }
-                                        slice_start = None;
-                                    } else {
-                                        println!("Bad time description format");
-                                        return None;
+
+                                        _ => {
+                                            return Err(SdpError::BadTime(ErrorLocation::locate(
+                                                self, line_start,
+                                            )));
+                                        }
                                     }
+                                    slice_start = None;
                                 }
 
-                                Operator::R => {}
+                                Operator::R => {
+                                    match (
+                                        times.last_mut(),
+                                        slice_start.and_then(|slice_start| {
+                                            RepeatTime::parse(&self[slice_start..i])
+                                        }),
+                                    ) {
+                                        (Some(time_description), Some(repeat)) => {
+                                            time_description.repeats.push(repeat)
+                                        }
+                                        _ => {
+                                            return Err(SdpError::BadRepeat(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::Z => {
+                                    match slice_start.and_then(|slice_start| {
+                                        TimeZoneAdjustment::parse_list(&self[slice_start..i])
+                                    }) {
+                                        Some(adjustments) => time_zone_adjustments = adjustments,
+                                        None => {
+                                            return Err(SdpError::BadTimeZone(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::K => {
+                                    match slice_start.and_then(|slice_start| {
+                                        KeyMethod::parse(&self[slice_start..i])
+                                    }) {
+                                        Some(key_method) => {
+                                            encryption_key.replace(key_method);
+                                        }
+                                        None => {
+                                            return Err(SdpError::BadKey(ErrorLocation::locate(
+                                                self, line_start,
+                                            )));
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
 
                                 // Sometimes a= comes after t=
                                 Operator::A => {
                                     if let Some(slice_start) = slice_start {
-                                        attributes.push(&self[slice_start..i]);
+                                        attributes.push(Attribute::parse(&self[slice_start..i]));
                                     }
                                     slice_start = None;
                                 }
 
                                 _ => {
-                                    println!("Unknown description in time section");
-                                    return None;
+                                    return Err(SdpError::OutOfOrder(ErrorLocation::locate(
+                                        self, line_start,
+                                    )));
                                 }
                             },
 
-                            Section::Media(_, _, _, formats, connection, attributes) => match &op {
+                            Section::Media(
+                                media_type,
+                                port_pair,
+                                protocol,
+                                formats,
+                                title,
+                                connection,
+                                bandwidths,
+                                key,
+                                attributes,
+                            ) => match &op {
                                 Operator::None => {
-                                    if let Some(slice_start) = slice_start {
+                                    if let (Some(_), Some(_), Some(_), Some(slice_start)) =
+                                        (&media_type, &port_pair, &protocol, slice_start)
+                                    {
                                         if let Some(formats) = formats {
                                             formats.push(&self[slice_start..i]);
                                         } else {
-                                            let mut v = Vec::new();
-                                            v.push(&self[slice_start..i]);
-                                            formats.replace(v);
+                                            formats.replace(vec![&self[slice_start..i]]);
                                         }
                                     }
+                                    // `m=<media> <port> <proto> <fmt> ...` needs at least one format
+                                    if formats.is_none() {
+                                        return Err(SdpError::BadMedia(ErrorLocation::locate(
+                                            self, line_start,
+                                        )));
+                                    }
                                     slice_start = None;
                                 }
 
-                                Operator::I => {}
+                                Operator::I => {
+                                    if let Some(slice_start) = slice_start {
+                                        title.replace(&self[slice_start..i]);
+                                    }
+                                    slice_start = None;
+                                }
 
                                 Operator::C(network_type, address_type) => {
                                     match (network_type, address_type) {
@@ -481,23 +1252,46 @@ impl<'a> AsSDP<'a> for [u8] {
                                         }
 
                                         _ => {
-                                            println!("Incomplete connection information",);
-                                            return None;
+                                            return Err(SdpError::BadConnection(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
                                         }
                                     }
                                 }
 
-                                Operator::B | Operator::K => {}
+                                Operator::B => {
+                                    if let Some(slice_start) = slice_start {
+                                        match Bandwidth::parse(&self[slice_start..i]) {
+                                            Some(bandwidth) => bandwidths.push(bandwidth),
+                                            None => {
+                                                return Err(SdpError::BadBandwidth(
+                                                    ErrorLocation::locate(self, line_start),
+                                                ));
+                                            }
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
+
+                                Operator::K => {
+                                    match slice_start.and_then(|slice_start| {
+                                        KeyMethod::parse(&self[slice_start..i])
+                                    }) {
+                                        Some(key_method) => {
+                                            key.replace(key_method);
+                                        }
+                                        None => {
+                                            return Err(SdpError::BadKey(ErrorLocation::locate(
+                                                self, line_start,
+                                            )));
+                                        }
+                                    }
+                                    slice_start = None;
+                                }
 
                                 Operator::A => {
                                     if let Some(slice_start) = slice_start {
-                                        if let Some(attributes) = attributes {
-                                            attributes.push(&self[slice_start..i]);
-                                        } else {
-                                            let mut v = Vec::new();
-                                            v.push(&self[slice_start..i]);
-                                            attributes.replace(v);
-                                        }
+                                        attributes.push(Attribute::parse(&self[slice_start..i]));
                                     }
                                     slice_start = None;
                                 }
@@ -513,8 +1307,9 @@ impl<'a> AsSDP<'a> for [u8] {
                         match &mut section {
                             Section::Main => match &mut op {
                                 Operator::V => {
-                                    println!("Duplicated protocol version");
-                                    return None;
+                                    return Err(SdpError::BadVersion(ErrorLocation::locate(
+                                        self, line_start,
+                                    )));
                                 }
 
                                 Operator::O(
@@ -567,18 +1362,22 @@ impl<'a> AsSDP<'a> for [u8] {
                                         }
 
                                         _ => {
-                                            println!("Bad originator and session identifier",);
-                                            return None;
+                                            return Err(SdpError::BadOrigin(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
                                         }
                                     }
                                 }
 
                                 Operator::S => {
-                                    println!("Duplicated session name");
-                                    return None;
+                                    // `s= ` is what RFC 8866 suggests when there is no name
+                                    slice_start.get_or_insert(i);
+                                    freeform = true;
                                 }
 
-                                Operator::I | Operator::U | Operator::E | Operator::P => {}
+                                Operator::I | Operator::U | Operator::E | Operator::P => {
+                                    freeform = true;
+                                }
 
                                 Operator::C(network_type, address_type) => {
                                     match (&network_type, &address_type) {
@@ -597,143 +1396,175 @@ impl<'a> AsSDP<'a> for [u8] {
                                         }
 
                                         _ => {
-                                            println!("Bad connection information");
-                                            return None;
+                                            return Err(SdpError::BadConnection(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
                                         }
                                     }
                                 }
 
-                                Operator::B | Operator::Z | Operator::K => {}
+                                Operator::B => {
+                                    freeform = true;
+                                }
+
+                                Operator::Z => {
+                                    freeform = true;
+                                }
+
+                                Operator::K => {
+                                    freeform = true;
+                                }
 
                                 Operator::A => {
                                     freeform = true;
                                 }
 
                                 _ => {
-                                    println!("Unknown description in main section");
-                                    return None;
+                                    return Err(SdpError::OutOfOrder(ErrorLocation::locate(
+                                        self, line_start,
+                                    )));
                                 }
                             },
 
                             Section::Time => match &mut op {
                                 Operator::T(start_time) => {
+                                    if start_time.is_some() {
+                                        return Err(SdpError::BadTime(ErrorLocation::locate(
+                                            self, line_start,
+                                        )));
+                                    }
                                     if let Some(slice_start) = slice_start {
                                         start_time.replace(&self[slice_start..i]);
                                     }
                                     slice_start = None;
                                 }
 
-                                Operator::R => {}
-
-                                Operator::A => {
+                                Operator::R | Operator::Z | Operator::K | Operator::A => {
                                     freeform = true;
                                 }
 
                                 _ => {
-                                    println!("Unknown description in time section");
-                                    return None;
+                                    return Err(SdpError::OutOfOrder(ErrorLocation::locate(
+                                        self, line_start,
+                                    )));
                                 }
                             },
 
-                            Section::Media(media_type, port_pair, protocol, formats, _, _) => {
-                                match &mut op {
-                                    Operator::None => {
-                                        match (&media_type, &port_pair, &protocol, &formats) {
-                                            (Some(_), Some(_), Some(_), _) => {
-                                                if let Some(slice_start) = slice_start {
-                                                    if let Some(formats) = formats {
-                                                        formats.push(&self[slice_start..i]);
-                                                    } else {
-                                                        let mut v = Vec::new();
-                                                        v.push(&self[slice_start..i]);
-                                                        formats.replace(v);
-                                                    }
+                            Section::Media(
+                                media_type,
+                                port_pair,
+                                protocol,
+                                formats,
+                                _,
+                                _,
+                                _,
+                                _,
+                                _,
+                            ) => match &mut op {
+                                Operator::None => {
+                                    match (&media_type, &port_pair, &protocol, &formats) {
+                                        (Some(_), Some(_), Some(_), _) => {
+                                            if let Some(slice_start) = slice_start {
+                                                if let Some(formats) = formats {
+                                                    formats.push(&self[slice_start..i]);
+                                                } else {
+                                                    formats.replace(vec![&self[slice_start..i]]);
                                                 }
-                                                slice_start = None;
                                             }
+                                            slice_start = None;
+                                        }
 
-                                            (Some(_), Some(_), None, None) => {
-                                                if let Some(slice_start) = slice_start {
-                                                    protocol.replace(&self[slice_start..i]);
-                                                }
-                                                slice_start = None;
+                                        (Some(_), Some(_), None, None) => {
+                                            if let Some(slice_start) = slice_start {
+                                                protocol.replace(&self[slice_start..i]);
                                             }
+                                            slice_start = None;
+                                        }
 
-                                            (Some(_), None, None, None) => {
-                                                if let Some(slice_start) = slice_start {
-                                                    let slice = &self[slice_start..i];
-                                                    let mut iter = slice.iter();
-                                                    if let Some(idx) = iter.position(|c| *c == b'/')
-                                                    {
-                                                        if let (Ok(port), Ok(number_of_ports)) = (
-                                                            slice[..idx].to_int::<u16>(),
-                                                            slice[idx + 1..].to_int::<i32>(),
-                                                        ) {
-                                                            port_pair
-                                                                .replace((port, number_of_ports));
-                                                        } else {
-                                                            println!("Bad media connection information format");
-                                                            return None;
-                                                        }
+                                        (Some(_), None, None, None) => {
+                                            if let Some(slice_start) = slice_start {
+                                                let slice = &self[slice_start..i];
+                                                let mut iter = slice.iter();
+                                                if let Some(idx) = iter.position(|c| *c == b'/') {
+                                                    if let (Ok(port), Ok(number_of_ports)) = (
+                                                        slice[..idx].to_int::<u16>(),
+                                                        slice[idx + 1..].to_int::<i32>(),
+                                                    ) {
+                                                        port_pair.replace((port, number_of_ports));
+                                                    } else {
+                                                        return Err(SdpError::BadMediaPort(
+                                                            ErrorLocation::locate(self, line_start),
+                                                        ));
+                                                    }
+                                                } else {
+                                                    if let Ok(port) = slice.to_int::<u16>() {
+                                                        port_pair.replace((port, 1));
                                                     } else {
-                                                        if let Ok(port) = slice.to_int::<u16>() {
-                                                            port_pair.replace((port, 1));
-                                                        } else {
-                                                            println!("Bad media connection information format");
-                                                            return None;
-                                                        }
+                                                        return Err(SdpError::BadMediaPort(
+                                                            ErrorLocation::locate(self, line_start),
+                                                        ));
                                                     }
                                                 }
-                                                slice_start = None;
                                             }
+                                            slice_start = None;
+                                        }
 
-                                            (None, None, None, None) => {
-                                                if let Some(slice_start) = slice_start {
-                                                    media_type.replace(&self[slice_start..i]);
-                                                }
-                                                slice_start = None;
+                                        (None, None, None, None) => {
+                                            if let Some(slice_start) = slice_start {
+                                                media_type.replace(&self[slice_start..i]);
                                             }
-
-                                            _ => {}
+                                            slice_start = None;
                                         }
+
+                                        _ => {}
                                     }
+                                }
 
-                                    Operator::I => {}
+                                Operator::I => {
+                                    freeform = true;
+                                }
 
-                                    Operator::C(network_type, address_type) => {
-                                        match (&network_type, &address_type) {
-                                            (Some(_), None) => {
-                                                if let Some(slice_start) = slice_start {
-                                                    address_type.replace(&self[slice_start..i]);
-                                                }
-                                                slice_start = None;
+                                Operator::C(network_type, address_type) => {
+                                    match (&network_type, &address_type) {
+                                        (Some(_), None) => {
+                                            if let Some(slice_start) = slice_start {
+                                                address_type.replace(&self[slice_start..i]);
                                             }
+                                            slice_start = None;
+                                        }
 
-                                            (None, None) => {
-                                                if let Some(slice_start) = slice_start {
-                                                    network_type.replace(&self[slice_start..i]);
-                                                }
-                                                slice_start = None;
+                                        (None, None) => {
+                                            if let Some(slice_start) = slice_start {
+                                                network_type.replace(&self[slice_start..i]);
                                             }
+                                            slice_start = None;
+                                        }
 
-                                            _ => {
-                                                println!("Bad connection information");
-                                                return None;
-                                            }
+                                        _ => {
+                                            return Err(SdpError::BadConnection(
+                                                ErrorLocation::locate(self, line_start),
+                                            ));
                                         }
                                     }
-                                    Operator::B | Operator::K => {}
+                                }
+                                Operator::B => {
+                                    freeform = true;
+                                }
 
-                                    Operator::A => {
-                                        freeform = true;
-                                    }
+                                Operator::K => {
+                                    freeform = true;
+                                }
 
-                                    _ => {
-                                        println!("Unknown description in media section",);
-                                        return None;
-                                    }
+                                Operator::A => {
+                                    freeform = true;
                                 }
-                            }
+
+                                _ => {
+                                    return Err(SdpError::OutOfOrder(ErrorLocation::locate(
+                                        self, line_start,
+                                    )));
+                                }
+                            },
                         }
 
                         if !freeform {
@@ -747,48 +1578,111 @@ impl<'a> AsSDP<'a> for [u8] {
                 }
 
                 Phase::Set => {
-                    if b == b'=' {
-                        match &mut op {
+                    if b != b'=' {
+                        return Err(SdpError::BadLine(ErrorLocation::locate(self, line_start)));
+                    }
+
+                    if let Operator::None = next_op {
+                        // Unknown type letters are ignored
+                        phase = Phase::SkippingError;
+                        i += 1;
+                        continue;
+                    }
+
+                    let allowed = match &section {
+                        Section::Main => {
+                            op.get_order() < next_op.get_order() && !matches!(next_op, Operator::R)
+                        }
+
+                        // t= and r= alternate until z=, k= or a= closes the time section
+                        Section::Time => match next_op {
                             Operator::T(_) | Operator::R => {
-                                section = Section::Time;
+                                matches!(op, Operator::T(_) | Operator::R)
                             }
+                            _ => op.get_order() < next_op.get_order(),
+                        },
+
+                        Section::Media(_, _, _, _, _, _, _, _, _) => {
+                            op.get_order() < next_op.get_order()
+                                && matches!(
+                                    next_op,
+                                    Operator::I
+                                        | Operator::C(_, _)
+                                        | Operator::B
+                                        | Operator::K
+                                        | Operator::A
+                                        | Operator::M
+                                )
+                        }
+                    } || (op.get_order() == next_op.get_order()
+                        && next_op.is_repeatable());
+
+                    if !allowed {
+                        if op.get_order() == next_op.get_order() {
+                            return Err(SdpError::Duplicated(ErrorLocation::locate(
+                                self, line_start,
+                            )));
+                        } else {
+                            return Err(SdpError::OutOfOrder(ErrorLocation::locate(
+                                self, line_start,
+                            )));
+                        }
+                    }
+
+                    op = std::mem::replace(&mut next_op, Operator::None);
+
+                    match &mut op {
+                        Operator::T(_) | Operator::R => {
+                            section = Section::Time;
+                        }
 
-                            Operator::M => {
-                                if let Section::Media(
-                                    Some(media_type),
-                                    Some(port_pair),
-                                    Some(protocol),
-                                    Some(formats),
+                        Operator::M => {
+                            if let Section::Media(
+                                Some(media_type),
+                                Some(port_pair),
+                                Some(protocol),
+                                Some(formats),
+                                title,
+                                connection,
+                                bandwidths,
+                                encryption_key,
+                                attributes,
+                            ) = section
+                            {
+                                let (port, number_of_ports) = port_pair;
+                                medias.push(Media {
+                                    media_type,
+                                    port,
+                                    number_of_ports,
+                                    protocol,
+                                    formats,
+                                    title,
                                     connection,
-                                    Some(attributes),
-                                ) = section
-                                {
-                                    let (port, number_of_ports) = port_pair;
-                                    medias.push(Media {
-                                        media_type,
-                                        port,
-                                        number_of_ports,
-                                        protocol,
-                                        formats,
-                                        connection,
-                                        attributes,
-                                    });
-                                }
-
-                                op = Operator::None;
-
-                                section = Section::Media(None, None, None, None, None, None);
+                                    bandwidths,
+                                    encryption_key,
+                                    attributes,
+                                });
                             }
 
-                            _ => {}
+                            op = Operator::None;
+
+                            section = Section::Media(
+                                None,
+                                None,
+                                None,
+                                None,
+                                None,
+                                None,
+                                Vec::new(),
+                                None,
+                                Vec::new(),
+                            );
                         }
 
-                        phase = Phase::Reading;
-                    } else if b == b'\r' || b == b'\n' {
-                        phase = Phase::Begin;
-                    } else {
-                        phase = Phase::SkippingError;
+                        _ => {}
                     }
+
+                    phase = Phase::Reading;
                 }
 
                 Phase::SkippingError => {
@@ -798,7 +1692,7 @@ impl<'a> AsSDP<'a> for [u8] {
                 }
             }
 
-            i = i + 1;
+            i += 1;
         }
 
         if let Section::Media(
@@ -806,8 +1700,11 @@ impl<'a> AsSDP<'a> for [u8] {
             Some(port_pair),
             Some(protocol),
             Some(formats),
+            title,
             connection,
-            Some(attributes),
+            bandwidths,
+            encryption_key,
+            attributes,
         ) = section
         {
             let (port, number_of_ports) = port_pair;
@@ -817,25 +1714,35 @@ impl<'a> AsSDP<'a> for [u8] {
                 number_of_ports,
                 protocol,
                 formats,
+                title,
                 connection,
+                bandwidths,
+                encryption_key,
                 attributes,
             });
         }
 
-        if let (Some(version), Some(origin), Some(session_name)) = (version, origin, session_name) {
-            Some(Sdp {
+        match (version, origin, session_name) {
+            (None, _, _) => Err(SdpError::MissingField('v')),
+            (_, None, _) => Err(SdpError::MissingField('o')),
+            (_, _, None) => Err(SdpError::MissingField('s')),
+            _ if times.is_empty() => Err(SdpError::MissingField('t')),
+            (Some(version), Some(origin), Some(session_name)) => Ok(Sdp {
                 version,
                 origin,
                 session_name,
+                information,
+                uri,
+                emails,
+                phones,
                 connection,
-                session_start_time,
-                session_end_time,
+                bandwidths,
+                times,
+                time_zone_adjustments,
+                encryption_key,
                 attributes,
                 medias,
-            })
-        } else {
-            println!("Incomplete sdp");
-            None
+            }),
         }
     }
 }
@@ -844,19 +1751,230 @@ impl<'a> fmt::Debug for Sdp<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut debug_struct = f.debug_struct("Sdp");
         debug_struct
-            .field("version", &String::from_utf8_lossy(&self.version))
+            .field("version", &String::from_utf8_lossy(self.version))
             .field("origin", &self.origin)
-            .field("session_name", &String::from_utf8_lossy(&self.session_name))
+            .field("session_name", &String::from_utf8_lossy(self.session_name))
+            .field(
+                "information",
+                &self
+                    .information
+                    .map(|information| String::from_utf8_lossy(information)),
+            )
+            .field("uri", &self.uri.map(|uri| String::from_utf8_lossy(uri)))
+            .field("emails", &self.emails)
+            .field("phones", &self.phones)
             .field("connection", &self.connection)
-            .field("start_time", &self.session_start_time)
-            .field("end_time", &self.session_end_time);
+            .field("bandwidths", &self.bandwidths)
+            .field("times", &self.times)
+            .field("time_zone_adjustments", &self.time_zone_adjustments)
+            .field("encryption_key", &self.encryption_key);
         for attribute in &self.attributes {
-            debug_struct.field("attribute", &String::from_utf8_lossy(attribute));
+            debug_struct.field("attribute", attribute);
         }
         debug_struct.field("medias", &self.medias).finish()
     }
 }
 
+impl<'a> Sdp<'a> {
+    /// First session level attribute called `name`
+    pub fn attribute(&self, name: &[u8]) -> Option<&Attribute<'a>> {
+        find_attribute(&self.attributes, name)
+    }
+
+    /// All session level attributes called `name`, in order of appearance
+    pub fn attributes_named<'s>(
+        &'s self,
+        name: &'s [u8],
+    ) -> impl Iterator<Item = &'s Attribute<'a>> + 's {
+        self.attributes
+            .iter()
+            .filter(move |attribute| attribute.name == name)
+    }
+
+    /// Whether a session level property attribute called `name` is present
+    pub fn has_flag(&self, name: &[u8]) -> bool {
+        find_flag(&self.attributes, name)
+    }
+
+    /// Whether a deprecated `k=` line is present at session or media level
+    pub fn has_deprecated_key(&self) -> bool {
+        self.encryption_key
+            .iter()
+            .chain(
+                self.medias
+                    .iter()
+                    .filter_map(|media| media.encryption_key.as_ref()),
+            )
+            .any(KeyMethod::is_deprecated)
+    }
+
+    /// Copies the session description out of the input buffer.
+    ///
+    /// Fails when the description does not serialize to something the
+    /// parser accepts, for example one with an empty session name.
+    pub fn to_sdp_owned(&self) -> Result<SdpOwned, SdpError> {
+        let mut raw = Vec::new();
+        // Writing into a Vec never fails
+        let _ = self.write_to(&mut raw);
+        raw[..].as_sdp()?;
+        Ok(SdpOwned { raw })
+    }
+
+    pub fn into_sdp_owned(self) -> Result<SdpOwned, SdpError> {
+        self.to_sdp_owned()
+    }
+
+    /// Writes the session description in RFC 4566 order, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"v=")?;
+        w.write_all(self.version)?;
+        w.write_all(b"\r\n")?;
+
+        self.origin.write_to(w)?;
+
+        w.write_all(b"s=")?;
+        w.write_all(self.session_name)?;
+        w.write_all(b"\r\n")?;
+
+        if let Some(information) = self.information {
+            w.write_all(b"i=")?;
+            w.write_all(information)?;
+            w.write_all(b"\r\n")?;
+        }
+
+        if let Some(uri) = self.uri {
+            w.write_all(b"u=")?;
+            w.write_all(uri)?;
+            w.write_all(b"\r\n")?;
+        }
+
+        for email in &self.emails {
+            w.write_all(b"e=")?;
+            email.write_to(w)?;
+            w.write_all(b"\r\n")?;
+        }
+
+        for phone in &self.phones {
+            w.write_all(b"p=")?;
+            phone.write_to(w)?;
+            w.write_all(b"\r\n")?;
+        }
+
+        if let Some(connection) = &self.connection {
+            connection.write_to(w)?;
+        }
+
+        for bandwidth in &self.bandwidths {
+            bandwidth.write_to(w)?;
+        }
+
+        for time_description in &self.times {
+            time_description.write_to(w)?;
+        }
+
+        if !self.time_zone_adjustments.is_empty() {
+            TimeZoneAdjustment::write_list_to(&self.time_zone_adjustments, w)?;
+        }
+
+        if let Some(encryption_key) = &self.encryption_key {
+            encryption_key.write_to(w)?;
+        }
+
+        for attribute in &self.attributes {
+            attribute.write_to(w)?;
+        }
+
+        for media in &self.medias {
+            media.write_to(w)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for Sdp<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// A session description that does not borrow from its input buffer.
+///
+/// It is kept in canonical wire format, so equality and hashing follow
+/// the serialized form. The bytes are checked once when it is created,
+/// `sdp()` borrows it back as a `Sdp`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SdpOwned {
+    raw: Vec<u8>,
+}
+
+impl SdpOwned {
+    pub fn sdp(&self) -> Sdp<'_> {
+        self.raw[..]
+            .as_sdp()
+            .expect("checked when the SdpOwned was created")
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.raw
+    }
+}
+
+impl<'a> TryFrom<Sdp<'a>> for SdpOwned {
+    type Error = SdpError;
+    fn try_from(sdp: Sdp<'a>) -> Result<SdpOwned, SdpError> {
+        sdp.into_sdp_owned()
+    }
+}
+
+impl<'a> TryFrom<&Sdp<'a>> for SdpOwned {
+    type Error = SdpError;
+    fn try_from(sdp: &Sdp<'a>) -> Result<SdpOwned, SdpError> {
+        sdp.to_sdp_owned()
+    }
+}
+
+impl TryFrom<&[u8]> for SdpOwned {
+    type Error = SdpError;
+    fn try_from(value: &[u8]) -> Result<SdpOwned, SdpError> {
+        value.as_sdp()?.into_sdp_owned()
+    }
+}
+
+impl fmt::Debug for SdpOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.sdp(), f)
+    }
+}
+
+impl fmt::Display for SdpOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(&self.raw))
+    }
+}
+
+/// Digits only, unlike `FromStr` which also takes a leading `+`
+pub(crate) fn parse_number<R: FromStr>(value: &[u8]) -> Option<R> {
+    if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    value.to_int::<R>().ok()
+}
+
+/// Space separated tokens, runs of spaces do not yield empty ones
+pub(crate) fn split_tokens(value: &[u8]) -> Vec<&[u8]> {
+    value
+        .split(|c| *c == b' ')
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
 trait ToInt {
     fn to_int<R>(&self) -> Result<R, String>
     where
@@ -870,15 +1988,13 @@ impl ToInt for [u8] {
     {
         match std::str::from_utf8(self) {
             Ok(s) => match R::from_str(s) {
-                Ok(i) => return Ok(i),
-                Err(_) => {
-                    return Err(String::from("std::num::ParseIntError"));
-                }
+                Ok(i) => Ok(i),
+                Err(_) => Err(String::from("std::num::ParseIntError")),
             },
             Err(e) => {
                 // std::str::Utf8Error
                 let s: String = format!("{}", e);
-                return Err(s);
+                Err(s)
             }
         }
     }
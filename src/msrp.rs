@@ -0,0 +1,397 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    io,
+};
+
+use crate::{parse_number, split_tokens, ConnectionData, Media, Sdp};
+
+/// An `msrp:` or `msrps:` URI of RFC 4975 section 9.
+///
+/// Equality follows RFC 4975 section 6.1: scheme, host and transport are
+/// compared case-insensitively, the session-id case-sensitively, an explicit
+/// port never matches an absent one, and userinfo and URI parameters are
+/// not significant.
+#[derive(Clone)]
+pub struct MsrpUri<'a> {
+    /// `msrp` or `msrps`
+    pub scheme: &'a [u8],
+    pub userinfo: Option<&'a [u8]>,
+    /// Host name, IPv4 address or bracketed IPv6 reference
+    pub host: &'a [u8],
+    pub port: Option<u16>,
+    pub session_id: Option<&'a [u8]>,
+    /// `tcp` or an extension transport, `tcp` is assumed when absent
+    pub transport: Option<&'a [u8]>,
+    /// URI parameters after the transport, without the leading `;`
+    pub parameters: Vec<&'a [u8]>,
+}
+
+impl<'a> MsrpUri<'a> {
+    pub fn parse(uri: &'a [u8]) -> Option<MsrpUri<'a>> {
+        let idx = uri.iter().position(|c| *c == b':')?;
+        let scheme = &uri[..idx];
+        if !scheme.eq_ignore_ascii_case(b"msrp") && !scheme.eq_ignore_ascii_case(b"msrps") {
+            return None;
+        }
+        let rest = uri[idx + 1..].strip_prefix(b"//")?;
+
+        let mut iter = rest.split(|c| *c == b';');
+        let before_parameters = iter.next()?;
+        let transport = iter.next();
+        if transport.is_some_and(|transport| transport.is_empty()) {
+            return None;
+        }
+        let parameters: Vec<&[u8]> = iter.collect();
+
+        let (authority, session_id) = match before_parameters.iter().position(|c| *c == b'/') {
+            Some(idx) => {
+                let session_id = &before_parameters[idx + 1..];
+                // session-id may itself contain '/', RFC 4975 section 9
+                if session_id.is_empty() {
+                    return None;
+                }
+                (&before_parameters[..idx], Some(session_id))
+            }
+            None => (before_parameters, None),
+        };
+
+        let (userinfo, hostport) = match authority.iter().rposition(|c| *c == b'@') {
+            Some(idx) => (Some(&authority[..idx]), &authority[idx + 1..]),
+            None => (None, authority),
+        };
+
+        let (host, port) = if hostport.starts_with(b"[") {
+            let idx = hostport.iter().position(|c| *c == b']')?;
+            match &hostport[idx + 1..] {
+                [] => (&hostport[..idx + 1], None),
+                [b':', port @ ..] => (&hostport[..idx + 1], Some(port)),
+                _ => return None,
+            }
+        } else {
+            match hostport.iter().position(|c| *c == b':') {
+                Some(idx) => (&hostport[..idx], Some(&hostport[idx + 1..])),
+                None => (hostport, None),
+            }
+        };
+
+        if host.is_empty() {
+            return None;
+        }
+
+        let port = match port {
+            Some(port) => Some(parse_number::<u16>(port)?),
+            None => None,
+        };
+
+        Some(MsrpUri {
+            scheme,
+            userinfo,
+            host,
+            port,
+            session_id,
+            transport,
+            parameters,
+        })
+    }
+
+    /// Whether the scheme is `msrps`
+    pub fn is_secure(&self) -> bool {
+        self.scheme.eq_ignore_ascii_case(b"msrps")
+    }
+
+    /// The transport, defaulting to `tcp`
+    pub fn transport(&self) -> &'a [u8] {
+        self.transport.unwrap_or(b"tcp")
+    }
+
+    /// Writes the URI as it appears in `a=path` or a To-Path/From-Path header
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(self.scheme)?;
+        w.write_all(b"://")?;
+        if let Some(userinfo) = self.userinfo {
+            w.write_all(userinfo)?;
+            w.write_all(b"@")?;
+        }
+        w.write_all(self.host)?;
+        if let Some(port) = self.port {
+            write!(w, ":{}", port)?;
+        }
+        if let Some(session_id) = self.session_id {
+            w.write_all(b"/")?;
+            w.write_all(session_id)?;
+        }
+        if let Some(transport) = self.transport {
+            w.write_all(b";")?;
+            w.write_all(transport)?;
+        }
+        for parameter in &self.parameters {
+            w.write_all(b";")?;
+            w.write_all(parameter)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> PartialEq for MsrpUri<'a> {
+    fn eq(&self, other: &MsrpUri) -> bool {
+        self.scheme.eq_ignore_ascii_case(other.scheme)
+            && self.host.eq_ignore_ascii_case(other.host)
+            && self.port == other.port
+            && self.session_id == other.session_id
+            && self.transport().eq_ignore_ascii_case(other.transport())
+    }
+}
+
+impl<'a> Eq for MsrpUri<'a> {}
+
+impl<'a> Hash for MsrpUri<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.scheme.to_ascii_lowercase().hash(state);
+        self.host.to_ascii_lowercase().hash(state);
+        self.port.hash(state);
+        self.session_id.hash(state);
+        self.transport().to_ascii_lowercase().hash(state);
+    }
+}
+
+impl<'a> fmt::Display for MsrpUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+impl<'a> fmt::Debug for MsrpUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("MsrpUri");
+        debug_struct.field("scheme", &String::from_utf8_lossy(self.scheme));
+        if let Some(userinfo) = self.userinfo {
+            debug_struct.field("userinfo", &String::from_utf8_lossy(userinfo));
+        }
+        debug_struct.field("host", &String::from_utf8_lossy(self.host));
+        if let Some(port) = self.port {
+            debug_struct.field("port", &port);
+        }
+        if let Some(session_id) = self.session_id {
+            debug_struct.field("session_id", &String::from_utf8_lossy(session_id));
+        }
+        if let Some(transport) = self.transport {
+            debug_struct.field("transport", &String::from_utf8_lossy(transport));
+        }
+        for parameter in &self.parameters {
+            debug_struct.field("parameter", &String::from_utf8_lossy(parameter));
+        }
+        debug_struct.finish()
+    }
+}
+
+/// The MSRP attributes of a `message` media description
+#[derive(Clone, PartialEq, Eq)]
+pub struct Msrp<'a> {
+    /// `TCP/TLS/MSRP` rather than `TCP/MSRP`
+    pub secure: bool,
+    /// `a=path`, the URI of this endpoint comes last
+    pub path: Vec<MsrpUri<'a>>,
+    /// `a=accept-types`
+    pub accept_types: Vec<&'a [u8]>,
+    /// `a=accept-wrapped-types`
+    pub accept_wrapped_types: Vec<&'a [u8]>,
+    /// `a=max-size` in octets
+    pub max_size: Option<u64>,
+}
+
+impl<'a> fmt::Debug for Msrp<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let accept_types: Vec<_> = self
+            .accept_types
+            .iter()
+            .map(|t| String::from_utf8_lossy(t))
+            .collect();
+        let accept_wrapped_types: Vec<_> = self
+            .accept_wrapped_types
+            .iter()
+            .map(|t| String::from_utf8_lossy(t))
+            .collect();
+        f.debug_struct("Msrp")
+            .field("secure", &self.secure)
+            .field("path", &self.path)
+            .field("accept_types", &accept_types)
+            .field("accept_wrapped_types", &accept_wrapped_types)
+            .field("max_size", &self.max_size)
+            .finish()
+    }
+}
+
+impl<'a> Media<'a> {
+    /// Whether this is an `m=message` line over `TCP/MSRP` or `TCP/TLS/MSRP`
+    pub fn is_msrp(&self) -> bool {
+        self.media_type == b"message"
+            && (self.protocol == b"TCP/MSRP" || self.protocol == b"TCP/TLS/MSRP")
+    }
+
+    /// MSRP attributes of RFC 4975 section 8.
+    ///
+    /// `None` when this is not an MSRP media description or when `a=path`
+    /// or `a=max-size` is missing or malformed.
+    pub fn msrp(&self) -> Option<Msrp<'a>> {
+        if !self.is_msrp() {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        for uri in split_tokens(self.attribute(b"path")?.value?) {
+            path.push(MsrpUri::parse(uri)?);
+        }
+        if path.is_empty() {
+            return None;
+        }
+
+        let max_size = match self.attribute(b"max-size") {
+            Some(attribute) => Some(parse_number::<u64>(attribute.value?)?),
+            None => None,
+        };
+
+        Some(Msrp {
+            secure: self.protocol == b"TCP/TLS/MSRP",
+            path,
+            accept_types: self
+                .attribute(b"accept-types")
+                .and_then(|attribute| attribute.value)
+                .map(split_tokens)
+                .unwrap_or_default(),
+            accept_wrapped_types: self
+                .attribute(b"accept-wrapped-types")
+                .and_then(|attribute| attribute.value)
+                .map(split_tokens)
+                .unwrap_or_default(),
+            max_size,
+        })
+    }
+
+    /// Whether `a=msrp-cema` is present, RFC 6714
+    pub fn has_msrp_cema(&self) -> bool {
+        self.has_flag(b"msrp-cema")
+    }
+
+    fn effective_connection<'s>(&'s self, sdp: &'s Sdp<'a>) -> Option<&'s ConnectionData<'a>> {
+        self.connection.as_ref().or(sdp.connection.as_ref())
+    }
+
+    /// Host and port to open the MSRP connection to.
+    ///
+    /// With CEMA in use these come from `c=` and `m=` (RFC 6714 section 4),
+    /// otherwise from the last URI of `a=path` (RFC 4975 section 8.1), the
+    /// `m=` port standing in for a URI without one.
+    pub fn msrp_transport_address(&self, sdp: &Sdp<'a>, cema: bool) -> Option<(&'a [u8], u16)> {
+        if cema {
+            let connection = self.effective_connection(sdp)?;
+            Some((connection.connection_address, self.port))
+        } else {
+            let msrp = self.msrp()?;
+            let uri = msrp.path.last()?;
+            Some((unbracket(uri.host), uri.port.unwrap_or(self.port)))
+        }
+    }
+
+    /// Checks this MSRP media description of `sdp` on its own
+    pub fn msrp_diagnostics(&self, sdp: &Sdp<'a>) -> Vec<MsrpDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if !self.is_msrp() {
+            if self.has_msrp_cema() {
+                diagnostics.push(MsrpDiagnostic::CemaOutsideMsrp);
+            }
+            return diagnostics;
+        }
+
+        let msrp = match self.msrp() {
+            Some(msrp) => msrp,
+            None => {
+                diagnostics.push(MsrpDiagnostic::BadPath);
+                return diagnostics;
+            }
+        };
+
+        let connection = self.effective_connection(sdp);
+        if connection.is_none() {
+            diagnostics.push(MsrpDiagnostic::MissingConnection);
+        }
+
+        if let (Some(connection), Some(uri)) = (connection, msrp.path.last()) {
+            let matches = unbracket(uri.host).eq_ignore_ascii_case(connection.connection_address)
+                && uri.port.map_or(true, |port| port == self.port);
+            if !matches && !self.has_msrp_cema() {
+                diagnostics.push(MsrpDiagnostic::PathMismatch);
+            }
+        }
+
+        diagnostics
+    }
+}
+
+fn unbracket(host: &[u8]) -> &[u8] {
+    host.strip_prefix(b"[")
+        .and_then(|host| host.strip_suffix(b"]"))
+        .unwrap_or(host)
+}
+
+/// Inconsistent MSRP or CEMA signalling
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MsrpDiagnostic {
+    /// `a=msrp-cema` on a media description that is not MSRP
+    CemaOutsideMsrp,
+    /// `a=path` missing or malformed
+    BadPath,
+    /// No `c=` at either level to take the transport address from
+    MissingConnection,
+    /// Without CEMA `c=`/`m=` should match the `a=path` authority, an
+    /// intermediary probably rewrote them and the connection will not
+    /// reach the peer
+    PathMismatch,
+    /// The answer has `a=msrp-cema` but the offer did not
+    UnsolicitedCema,
+}
+
+impl fmt::Display for MsrpDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MsrpDiagnostic::CemaOutsideMsrp => f.write_str("msrp-cema on a non MSRP media"),
+            MsrpDiagnostic::BadPath => f.write_str("Missing or malformed path attribute"),
+            MsrpDiagnostic::MissingConnection => f.write_str("Missing connection data"),
+            MsrpDiagnostic::PathMismatch => {
+                f.write_str("Connection data does not match the path without CEMA")
+            }
+            MsrpDiagnostic::UnsolicitedCema => f.write_str("msrp-cema in answer but not in offer"),
+        }
+    }
+}
+
+/// Whether CEMA is in use, which requires `a=msrp-cema` in both offer and answer
+pub fn cema_in_use(offer: &Media, answer: &Media) -> bool {
+    offer.is_msrp() && answer.is_msrp() && offer.has_msrp_cema() && answer.has_msrp_cema()
+}
+
+/// Checks CEMA negotiation across an offer and its answer
+pub fn cema_diagnostics(offer: &Media, answer: &Media) -> Vec<MsrpDiagnostic> {
+    let mut diagnostics = Vec::new();
+    if answer.has_msrp_cema() && !offer.has_msrp_cema() {
+        diagnostics.push(MsrpDiagnostic::UnsolicitedCema);
+    }
+    diagnostics
+}
@@ -0,0 +1,410 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::{parse_number, Media, ToInt};
+
+fn parse_payload_type(value: &[u8]) -> Option<u8> {
+    match parse_number::<u8>(value)? {
+        payload_type if payload_type < 128 => Some(payload_type),
+        _ => None,
+    }
+}
+
+/// Splits `<payload type> <rest>`
+fn split_payload_type(value: &[u8]) -> Option<(u8, &[u8])> {
+    let idx = value.iter().position(|c| *c == b' ')?;
+    Some((parse_payload_type(&value[..idx])?, &value[idx + 1..]))
+}
+
+/// Value of an `a=rtpmap:<payload type> <encoding name>/<clock rate>[/<encoding parameters>]` line
+#[derive(Clone, PartialEq, Eq)]
+pub struct RtpMap<'a> {
+    pub payload_type: u8,
+    pub encoding_name: &'a [u8],
+    pub clock_rate: u32,
+    /// Number of audio channels, absent means one channel for audio streams
+    pub channels: Option<u32>,
+}
+
+impl<'a> RtpMap<'a> {
+    pub fn parse(value: &'a [u8]) -> Option<RtpMap<'a>> {
+        let (payload_type, value) = split_payload_type(value)?;
+        let mut iter = value.split(|c| *c == b'/');
+        let encoding_name = iter.next()?;
+        let clock_rate = iter.next()?;
+        let channels = iter.next();
+        if encoding_name.is_empty()
+            || clock_rate.is_empty()
+            || !clock_rate.iter().all(u8::is_ascii_digit)
+            || iter.next().is_some()
+        {
+            return None;
+        }
+        let channels = match channels {
+            Some(channels) if !channels.is_empty() && channels.iter().all(u8::is_ascii_digit) => {
+                Some(channels.to_int::<u32>().ok()?)
+            }
+            Some(_) => return None,
+            None => None,
+        };
+        Some(RtpMap {
+            payload_type,
+            encoding_name,
+            clock_rate: clock_rate.to_int::<u32>().ok()?,
+            channels,
+        })
+    }
+}
+
+impl<'a> fmt::Debug for RtpMap<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RtpMap")
+            .field("payload_type", &self.payload_type)
+            .field(
+                "encoding_name",
+                &String::from_utf8_lossy(self.encoding_name),
+            )
+            .field("clock_rate", &self.clock_rate)
+            .field("channels", &self.channels)
+            .finish()
+    }
+}
+
+const fn static_entry(
+    payload_type: u8,
+    encoding_name: &'static [u8],
+    clock_rate: u32,
+    channels: Option<u32>,
+) -> RtpMap<'static> {
+    RtpMap {
+        payload_type,
+        encoding_name,
+        clock_rate,
+        channels,
+    }
+}
+
+/// Static payload types of RFC 3551 tables 4 and 5, reserved and unassigned ones left out
+pub const STATIC_PAYLOAD_TYPES: &[RtpMap<'static>] = &[
+    static_entry(0, b"PCMU", 8000, None),
+    static_entry(3, b"GSM", 8000, None),
+    static_entry(4, b"G723", 8000, None),
+    static_entry(5, b"DVI4", 8000, None),
+    static_entry(6, b"DVI4", 16000, None),
+    static_entry(7, b"LPC", 8000, None),
+    static_entry(8, b"PCMA", 8000, None),
+    static_entry(9, b"G722", 8000, None),
+    static_entry(10, b"L16", 44100, Some(2)),
+    static_entry(11, b"L16", 44100, None),
+    static_entry(12, b"QCELP", 8000, None),
+    static_entry(13, b"CN", 8000, None),
+    static_entry(14, b"MPA", 90000, None),
+    static_entry(15, b"G728", 8000, None),
+    static_entry(16, b"DVI4", 11025, None),
+    static_entry(17, b"DVI4", 22050, None),
+    static_entry(18, b"G729", 8000, None),
+    static_entry(25, b"CelB", 90000, None),
+    static_entry(26, b"JPEG", 90000, None),
+    static_entry(28, b"nv", 90000, None),
+    static_entry(31, b"H261", 90000, None),
+    static_entry(32, b"MPV", 90000, None),
+    static_entry(33, b"MP2T", 90000, None),
+    static_entry(34, b"H263", 90000, None),
+];
+
+/// The RFC 3551 mapping of a static payload type
+pub fn static_payload_type(payload_type: u8) -> Option<RtpMap<'static>> {
+    STATIC_PAYLOAD_TYPES
+        .iter()
+        .find(|rtpmap| rtpmap.payload_type == payload_type)
+        .cloned()
+}
+
+/// Value of an `a=fmtp:<payload type> <parameters>` line
+#[derive(Clone, PartialEq, Eq)]
+pub struct Fmtp<'a> {
+    pub payload_type: u8,
+    /// Everything after the payload type, for codecs whose parameters are not `k=v` pairs
+    pub raw: &'a [u8],
+    /// `;` separated parameters in order of appearance, value is absent when there is no `=`
+    pub parameters: Vec<(&'a [u8], Option<&'a [u8]>)>,
+}
+
+impl<'a> Fmtp<'a> {
+    pub fn parse(value: &'a [u8]) -> Option<Fmtp<'a>> {
+        let (payload_type, raw) = split_payload_type(value)?;
+        let mut parameters = Vec::new();
+        for parameter in raw.split(|c| *c == b';') {
+            let parameter = parameter.trim_ascii();
+            if parameter.is_empty() {
+                continue;
+            }
+            match parameter.iter().position(|c| *c == b'=') {
+                Some(idx) => parameters.push((
+                    parameter[..idx].trim_ascii(),
+                    Some(parameter[idx + 1..].trim_ascii()),
+                )),
+                None => parameters.push((parameter, None)),
+            }
+        }
+        Some(Fmtp {
+            payload_type,
+            raw,
+            parameters,
+        })
+    }
+
+    /// Value of the first parameter called `name`, compared case-insensitively
+    pub fn get(&self, name: &[u8]) -> Option<&'a [u8]> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| *value)
+    }
+}
+
+impl<'a> fmt::Debug for Fmtp<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parameters: Vec<_> = self
+            .parameters
+            .iter()
+            .map(|(name, value)| {
+                (
+                    String::from_utf8_lossy(name),
+                    value.map(String::from_utf8_lossy),
+                )
+            })
+            .collect();
+        f.debug_struct("Fmtp")
+            .field("payload_type", &self.payload_type)
+            .field("parameters", &parameters)
+            .finish()
+    }
+}
+
+/// A payload type of the `m=` line along with its encoding and format parameters
+#[derive(Clone, PartialEq, Eq)]
+pub struct Codec<'a> {
+    pub payload_type: u8,
+    pub name: &'a [u8],
+    pub clock_rate: u32,
+    /// Number of audio channels, absent means one channel for audio streams
+    pub channels: Option<u32>,
+    pub fmtp: Option<Fmtp<'a>>,
+}
+
+impl<'a> fmt::Debug for Codec<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Codec")
+            .field("payload_type", &self.payload_type)
+            .field("name", &String::from_utf8_lossy(self.name))
+            .field("clock_rate", &self.clock_rate)
+            .field("channels", &self.channels)
+            .field("fmtp", &self.fmtp)
+            .finish()
+    }
+}
+
+/// Inconsistencies between the `rtpmap`/`fmtp` attributes and the `m=` line
+#[derive(Clone, PartialEq, Eq)]
+pub enum RtpDiagnostic<'a> {
+    /// An `rtpmap` value that does not follow the RFC 4566 syntax
+    MalformedRtpMap(&'a [u8]),
+    /// An `fmtp` value without a valid payload type
+    MalformedFmtp(&'a [u8]),
+    /// An `rtpmap` for a payload type absent from the formats of the `m=` line
+    RtpMapWithoutFormat(u8),
+    /// An `fmtp` for a payload type absent from the formats of the `m=` line
+    FmtpWithoutFormat(u8),
+    /// More than one `rtpmap` for the same payload type
+    DuplicatedRtpMap(u8),
+}
+
+impl<'a> fmt::Debug for RtpDiagnostic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RtpDiagnostic::MalformedRtpMap(value) => f
+                .debug_tuple("MalformedRtpMap")
+                .field(&String::from_utf8_lossy(value))
+                .finish(),
+            RtpDiagnostic::MalformedFmtp(value) => f
+                .debug_tuple("MalformedFmtp")
+                .field(&String::from_utf8_lossy(value))
+                .finish(),
+            RtpDiagnostic::RtpMapWithoutFormat(payload_type) => f
+                .debug_tuple("RtpMapWithoutFormat")
+                .field(payload_type)
+                .finish(),
+            RtpDiagnostic::FmtpWithoutFormat(payload_type) => f
+                .debug_tuple("FmtpWithoutFormat")
+                .field(payload_type)
+                .finish(),
+            RtpDiagnostic::DuplicatedRtpMap(payload_type) => f
+                .debug_tuple("DuplicatedRtpMap")
+                .field(payload_type)
+                .finish(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for RtpDiagnostic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RtpDiagnostic::MalformedRtpMap(value) => {
+                write!(f, "Malformed rtpmap: {}", String::from_utf8_lossy(value))
+            }
+            RtpDiagnostic::MalformedFmtp(value) => {
+                write!(f, "Malformed fmtp: {}", String::from_utf8_lossy(value))
+            }
+            RtpDiagnostic::RtpMapWithoutFormat(payload_type) => write!(
+                f,
+                "rtpmap for payload type {} which is not listed on the m= line",
+                payload_type
+            ),
+            RtpDiagnostic::FmtpWithoutFormat(payload_type) => write!(
+                f,
+                "fmtp for payload type {} which is not listed on the m= line",
+                payload_type
+            ),
+            RtpDiagnostic::DuplicatedRtpMap(payload_type) => {
+                write!(f, "More than one rtpmap for payload type {}", payload_type)
+            }
+        }
+    }
+}
+
+impl<'a> Media<'a> {
+    /// Payload types of the `m=` line, formats that are not payload types are skipped
+    pub fn payload_types(&self) -> impl Iterator<Item = u8> + '_ {
+        self.formats
+            .iter()
+            .filter_map(|format| parse_payload_type(format))
+    }
+
+    /// Well-formed `rtpmap` attributes by payload type, the first one wins
+    /// when a payload type is mapped twice
+    pub fn rtpmaps(&self) -> BTreeMap<u8, RtpMap<'a>> {
+        let mut rtpmaps = BTreeMap::new();
+        for rtpmap in self
+            .attributes_named(b"rtpmap")
+            .filter_map(|attribute| RtpMap::parse(attribute.value?))
+        {
+            rtpmaps.entry(rtpmap.payload_type).or_insert(rtpmap);
+        }
+        rtpmaps
+    }
+
+    /// The first `rtpmap` for `payload_type`
+    pub fn rtpmap(&self, payload_type: u8) -> Option<RtpMap<'a>> {
+        self.attributes_named(b"rtpmap")
+            .filter_map(|attribute| RtpMap::parse(attribute.value?))
+            .find(|rtpmap| rtpmap.payload_type == payload_type)
+    }
+
+    /// Well-formed `fmtp` attributes by payload type, the first one wins
+    pub fn fmtps(&self) -> BTreeMap<u8, Fmtp<'a>> {
+        let mut fmtps = BTreeMap::new();
+        for fmtp in self
+            .attributes_named(b"fmtp")
+            .filter_map(|attribute| Fmtp::parse(attribute.value?))
+        {
+            fmtps.entry(fmtp.payload_type).or_insert(fmtp);
+        }
+        fmtps
+    }
+
+    /// The first `fmtp` for `payload_type`
+    pub fn fmtp(&self, payload_type: u8) -> Option<Fmtp<'a>> {
+        self.attributes_named(b"fmtp")
+            .filter_map(|attribute| Fmtp::parse(attribute.value?))
+            .find(|fmtp| fmtp.payload_type == payload_type)
+    }
+
+    /// Codecs in the order of the `m=` line.
+    ///
+    /// Explicit `rtpmap` attributes take precedence over the RFC 3551 static
+    /// payload types, which only apply to RTP profiles. Dynamic payload types
+    /// without an `rtpmap` are left out.
+    pub fn codecs(&self) -> Vec<Codec<'a>> {
+        let is_rtp = self.protocol.windows(4).any(|w| w == b"RTP/");
+        let rtpmaps = self.rtpmaps();
+        let fmtps = self.fmtps();
+
+        let mut codecs: Vec<Codec<'a>> = Vec::new();
+        for payload_type in self.payload_types() {
+            if codecs
+                .iter()
+                .any(|codec| codec.payload_type == payload_type)
+            {
+                continue;
+            }
+            let rtpmap = match rtpmaps.get(&payload_type) {
+                Some(rtpmap) => rtpmap.clone(),
+                None if is_rtp => match static_payload_type(payload_type) {
+                    Some(rtpmap) => rtpmap,
+                    None => continue,
+                },
+                None => continue,
+            };
+            codecs.push(Codec {
+                payload_type,
+                name: rtpmap.encoding_name,
+                clock_rate: rtpmap.clock_rate,
+                channels: rtpmap.channels,
+                fmtp: fmtps.get(&payload_type).cloned(),
+            });
+        }
+
+        codecs
+    }
+
+    /// Checks `rtpmap` and `fmtp` attributes against the formats of the `m=` line
+    pub fn rtp_diagnostics(&self) -> Vec<RtpDiagnostic<'a>> {
+        let mut diagnostics = Vec::new();
+        let mut mapped: Vec<u8> = Vec::new();
+
+        for attribute in &self.attributes {
+            let value = attribute.value.unwrap_or_default();
+            if attribute.name == b"rtpmap" {
+                match RtpMap::parse(value) {
+                    Some(rtpmap) => {
+                        if mapped.contains(&rtpmap.payload_type) {
+                            diagnostics.push(RtpDiagnostic::DuplicatedRtpMap(rtpmap.payload_type));
+                        } else {
+                            mapped.push(rtpmap.payload_type);
+                        }
+                        if !self.payload_types().any(|pt| pt == rtpmap.payload_type) {
+                            diagnostics
+                                .push(RtpDiagnostic::RtpMapWithoutFormat(rtpmap.payload_type));
+                        }
+                    }
+                    None => diagnostics.push(RtpDiagnostic::MalformedRtpMap(value)),
+                }
+            } else if attribute.name == b"fmtp" {
+                match Fmtp::parse(value) {
+                    Some(fmtp) => {
+                        if !self.payload_types().any(|pt| pt == fmtp.payload_type) {
+                            diagnostics.push(RtpDiagnostic::FmtpWithoutFormat(fmtp.payload_type));
+                        }
+                    }
+                    None => diagnostics.push(RtpDiagnostic::MalformedFmtp(value)),
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt, io};
+
+use crate::{base64, parse_number, Media};
+
+/// Crypto suites of RFC 4568, RFC 6188 and RFC 7714
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CryptoSuite<'a> {
+    AesCm128HmacSha1_80,
+    AesCm128HmacSha1_32,
+    F8_128HmacSha1_80,
+    Aes192CmHmacSha1_80,
+    Aes192CmHmacSha1_32,
+    Aes256CmHmacSha1_80,
+    Aes256CmHmacSha1_32,
+    AeadAes128Gcm,
+    AeadAes256Gcm,
+    Other(&'a [u8]),
+}
+
+impl<'a> CryptoSuite<'a> {
+    pub fn from_bytes(name: &'a [u8]) -> CryptoSuite<'a> {
+        match name {
+            b"AES_CM_128_HMAC_SHA1_80" => CryptoSuite::AesCm128HmacSha1_80,
+            b"AES_CM_128_HMAC_SHA1_32" => CryptoSuite::AesCm128HmacSha1_32,
+            b"F8_128_HMAC_SHA1_80" => CryptoSuite::F8_128HmacSha1_80,
+            b"AES_192_CM_HMAC_SHA1_80" => CryptoSuite::Aes192CmHmacSha1_80,
+            b"AES_192_CM_HMAC_SHA1_32" => CryptoSuite::Aes192CmHmacSha1_32,
+            b"AES_256_CM_HMAC_SHA1_80" => CryptoSuite::Aes256CmHmacSha1_80,
+            b"AES_256_CM_HMAC_SHA1_32" => CryptoSuite::Aes256CmHmacSha1_32,
+            b"AEAD_AES_128_GCM" => CryptoSuite::AeadAes128Gcm,
+            b"AEAD_AES_256_GCM" => CryptoSuite::AeadAes256Gcm,
+            other => CryptoSuite::Other(other),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            CryptoSuite::AesCm128HmacSha1_80 => b"AES_CM_128_HMAC_SHA1_80",
+            CryptoSuite::AesCm128HmacSha1_32 => b"AES_CM_128_HMAC_SHA1_32",
+            CryptoSuite::F8_128HmacSha1_80 => b"F8_128_HMAC_SHA1_80",
+            CryptoSuite::Aes192CmHmacSha1_80 => b"AES_192_CM_HMAC_SHA1_80",
+            CryptoSuite::Aes192CmHmacSha1_32 => b"AES_192_CM_HMAC_SHA1_32",
+            CryptoSuite::Aes256CmHmacSha1_80 => b"AES_256_CM_HMAC_SHA1_80",
+            CryptoSuite::Aes256CmHmacSha1_32 => b"AES_256_CM_HMAC_SHA1_32",
+            CryptoSuite::AeadAes128Gcm => b"AEAD_AES_128_GCM",
+            CryptoSuite::AeadAes256Gcm => b"AEAD_AES_256_GCM",
+            CryptoSuite::Other(name) => name,
+        }
+    }
+
+    /// Length in octets of the master key followed by the master salt
+    pub fn key_salt_len(&self) -> Option<usize> {
+        match self {
+            CryptoSuite::AesCm128HmacSha1_80
+            | CryptoSuite::AesCm128HmacSha1_32
+            | CryptoSuite::F8_128HmacSha1_80 => Some(30),
+            CryptoSuite::Aes192CmHmacSha1_80 | CryptoSuite::Aes192CmHmacSha1_32 => Some(38),
+            CryptoSuite::Aes256CmHmacSha1_80 | CryptoSuite::Aes256CmHmacSha1_32 => Some(46),
+            CryptoSuite::AeadAes128Gcm => Some(28),
+            CryptoSuite::AeadAes256Gcm => Some(44),
+            CryptoSuite::Other(_) => None,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for CryptoSuite<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+/// Master key lifetime in packets
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyLifetime {
+    Packets(u64),
+    /// Written as `2^n`
+    PowerOfTwo(u8),
+}
+
+impl KeyLifetime {
+    fn parse(value: &[u8]) -> Option<KeyLifetime> {
+        match value.strip_prefix(b"2^") {
+            Some(exponent) => match parse_number::<u8>(exponent)? {
+                exponent if exponent < 64 => Some(KeyLifetime::PowerOfTwo(exponent)),
+                _ => None,
+            },
+            None => Some(KeyLifetime::Packets(parse_number(value)?)),
+        }
+    }
+
+    pub fn packets(&self) -> u64 {
+        match self {
+            KeyLifetime::Packets(packets) => *packets,
+            KeyLifetime::PowerOfTwo(exponent) => 1 << exponent,
+        }
+    }
+}
+
+/// `inline:<key||salt>[|lifetime][|MKI:length]`
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyParam<'a> {
+    /// Base64 decoded master key followed by master salt
+    pub key_salt: Vec<u8>,
+    pub lifetime: Option<KeyLifetime>,
+    /// MKI value as written, up to 128 digits, and its length in octets on the wire
+    pub mki: Option<(&'a [u8], u8)>,
+}
+
+/// `mki-value = 1*128DIGIT` of RFC 4568 section 9.2
+fn is_mki_value(value: &[u8]) -> bool {
+    (1..=128).contains(&value.len()) && value.iter().all(u8::is_ascii_digit)
+}
+
+impl<'a> KeyParam<'a> {
+    fn parse(value: &'a [u8]) -> Option<KeyParam<'a>> {
+        let value = value.strip_prefix(b"inline:")?;
+        let mut iter = value.split(|c| *c == b'|');
+        let key_salt = base64::decode(iter.next()?)?;
+
+        let mut key_param = KeyParam {
+            key_salt,
+            lifetime: None,
+            mki: None,
+        };
+
+        for part in iter {
+            match part.iter().position(|c| *c == b':') {
+                Some(idx) if key_param.mki.is_none() => {
+                    let length = parse_number::<u8>(&part[idx + 1..])?;
+                    if !(1..=128).contains(&length) {
+                        return None;
+                    }
+                    let mki_value = &part[..idx];
+                    if !is_mki_value(mki_value) {
+                        return None;
+                    }
+                    key_param.mki.replace((mki_value, length));
+                }
+                None if key_param.lifetime.is_none() && key_param.mki.is_none() => {
+                    key_param.lifetime.replace(KeyLifetime::parse(part)?);
+                }
+                _ => return None,
+            }
+        }
+
+        Some(key_param)
+    }
+
+    fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"inline:")?;
+        w.write_all(&base64::encode(&self.key_salt))?;
+        match self.lifetime {
+            Some(KeyLifetime::Packets(packets)) => write!(w, "|{}", packets)?,
+            Some(KeyLifetime::PowerOfTwo(exponent)) => write!(w, "|2^{}", exponent)?,
+            None => {}
+        }
+        if let Some((value, length)) = self.mki {
+            w.write_all(b"|")?;
+            w.write_all(value)?;
+            write!(w, ":{}", length)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for KeyParam<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Keeps the key material out of logs
+        let mut debug_struct = f.debug_struct("KeyParam");
+        debug_struct
+            .field("key_salt_len", &self.key_salt.len())
+            .field("lifetime", &self.lifetime);
+        if let Some((value, length)) = self.mki {
+            debug_struct.field("mki", &(String::from_utf8_lossy(value), length));
+        }
+        debug_struct.finish()
+    }
+}
+
+/// `FEC_ORDER` session parameter
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FecOrder {
+    FecSrtp,
+    SrtpFec,
+}
+
+/// Session parameters of RFC 4568 section 6.3
+#[derive(Clone, PartialEq, Eq)]
+pub enum SessionParam<'a> {
+    /// Key derivation rate as a power of two, 0 to 24
+    Kdr(u8),
+    UnencryptedSrtp,
+    UnencryptedSrtcp,
+    UnauthenticatedSrtp,
+    FecOrder(FecOrder),
+    /// Key parameters for FEC, kept as written
+    FecKey(&'a [u8]),
+    /// Window size hint, at least 64
+    Wsh(u32),
+    /// Unknown parameter, kept as written
+    Other(&'a [u8]),
+}
+
+impl<'a> SessionParam<'a> {
+    fn parse(value: &'a [u8]) -> Option<SessionParam<'a>> {
+        if let Some(kdr) = value.strip_prefix(b"KDR=") {
+            match parse_number::<u8>(kdr)? {
+                kdr if kdr <= 24 => Some(SessionParam::Kdr(kdr)),
+                _ => None,
+            }
+        } else if let Some(fec_order) = value.strip_prefix(b"FEC_ORDER=") {
+            match fec_order {
+                b"FEC_SRTP" => Some(SessionParam::FecOrder(FecOrder::FecSrtp)),
+                b"SRTP_FEC" => Some(SessionParam::FecOrder(FecOrder::SrtpFec)),
+                _ => None,
+            }
+        } else if let Some(fec_key) = value.strip_prefix(b"FEC_KEY=") {
+            Some(SessionParam::FecKey(fec_key))
+        } else if let Some(wsh) = value.strip_prefix(b"WSH=") {
+            match parse_number::<u32>(wsh)? {
+                wsh if wsh >= 64 => Some(SessionParam::Wsh(wsh)),
+                _ => None,
+            }
+        } else {
+            match value {
+                b"UNENCRYPTED_SRTP" => Some(SessionParam::UnencryptedSrtp),
+                b"UNENCRYPTED_SRTCP" => Some(SessionParam::UnencryptedSrtcp),
+                b"UNAUTHENTICATED_SRTP" => Some(SessionParam::UnauthenticatedSrtp),
+                [] => None,
+                other => Some(SessionParam::Other(other)),
+            }
+        }
+    }
+
+    fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            SessionParam::Kdr(kdr) => write!(w, "KDR={}", kdr),
+            SessionParam::UnencryptedSrtp => w.write_all(b"UNENCRYPTED_SRTP"),
+            SessionParam::UnencryptedSrtcp => w.write_all(b"UNENCRYPTED_SRTCP"),
+            SessionParam::UnauthenticatedSrtp => w.write_all(b"UNAUTHENTICATED_SRTP"),
+            SessionParam::FecOrder(FecOrder::FecSrtp) => w.write_all(b"FEC_ORDER=FEC_SRTP"),
+            SessionParam::FecOrder(FecOrder::SrtpFec) => w.write_all(b"FEC_ORDER=SRTP_FEC"),
+            SessionParam::FecKey(fec_key) => {
+                w.write_all(b"FEC_KEY=")?;
+                w.write_all(fec_key)
+            }
+            SessionParam::Wsh(wsh) => write!(w, "WSH={}", wsh),
+            SessionParam::Other(other) => w.write_all(other),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for SessionParam<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Value of an `a=crypto` line, RFC 4568 section 9.1
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Crypto<'a> {
+    pub tag: u32,
+    pub suite: CryptoSuite<'a>,
+    pub key_params: Vec<KeyParam<'a>>,
+    pub session_params: Vec<SessionParam<'a>>,
+}
+
+impl<'a> Crypto<'a> {
+    /// Fails on malformed values, and on key material whose length does not
+    /// fit a known suite
+    pub fn parse(value: &'a [u8]) -> Option<Crypto<'a>> {
+        let mut iter = value.split(|c| *c == b' ');
+
+        let tag = iter.next()?;
+        if tag.len() > 9 {
+            return None;
+        }
+        let tag = parse_number::<u32>(tag)?;
+        let suite = iter.next()?;
+        if suite.is_empty() {
+            return None;
+        }
+        let suite = CryptoSuite::from_bytes(suite);
+
+        let mut key_params = Vec::new();
+        for key_param in iter.next()?.split(|c| *c == b';') {
+            let key_param = KeyParam::parse(key_param)?;
+            if suite
+                .key_salt_len()
+                .is_some_and(|len| len != key_param.key_salt.len())
+            {
+                return None;
+            }
+            key_params.push(key_param);
+        }
+
+        let mut session_params = Vec::new();
+        for session_param in iter {
+            session_params.push(SessionParam::parse(session_param)?);
+        }
+
+        Some(Crypto {
+            tag,
+            suite,
+            key_params,
+            session_params,
+        })
+    }
+
+    /// A fresh attribute carrying `key_salt` inline, `None` when its length
+    /// does not fit `suite`
+    pub fn generate(tag: u32, suite: CryptoSuite<'a>, key_salt: Vec<u8>) -> Option<Crypto<'a>> {
+        if tag > 999_999_999 || suite.key_salt_len()? != key_salt.len() {
+            return None;
+        }
+        Some(Crypto {
+            tag,
+            suite,
+            key_params: vec![KeyParam {
+                key_salt,
+                lifetime: None,
+                mki: None,
+            }],
+            session_params: Vec::new(),
+        })
+    }
+
+    /// The answer to this offered attribute, keeping its tag, suite and
+    /// session parameters with our own `key_salt`.
+    ///
+    /// `FEC_KEY` is left out as it carries the key material of the offerer.
+    pub fn answer(&self, key_salt: Vec<u8>) -> Option<Crypto<'a>> {
+        let mut crypto = Crypto::generate(self.tag, self.suite, key_salt)?;
+        crypto.session_params = self
+            .session_params
+            .iter()
+            .filter(|session_param| !matches!(session_param, SessionParam::FecKey(_)))
+            .cloned()
+            .collect();
+        Some(crypto)
+    }
+
+    /// Writes the `a=crypto` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"a=crypto:")?;
+        self.write_value_to(w)?;
+        w.write_all(b"\r\n")
+    }
+
+    /// Writes what follows `crypto:`
+    pub fn write_value_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{} ", self.tag)?;
+        w.write_all(self.suite.as_bytes())?;
+        for (idx, key_param) in self.key_params.iter().enumerate() {
+            w.write_all(if idx == 0 { b" " } else { b";" })?;
+            key_param.write_to(w)?;
+        }
+        for session_param in &self.session_params {
+            w.write_all(b" ")?;
+            session_param.write_to(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for Crypto<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Picks the offered attribute to answer, RFC 4568 section 7.1.2.
+///
+/// Offers are tried in order of preference of the offerer, the first one
+/// with a suite in `supported` wins. Offers with session parameters unknown
+/// to this crate are skipped as they cannot be honoured.
+pub fn select_crypto<'o, 'a>(
+    offered: &'o [Crypto<'a>],
+    supported: &[CryptoSuite],
+) -> Option<&'o Crypto<'a>> {
+    offered.iter().find(|crypto| {
+        supported.contains(&crypto.suite)
+            && !crypto
+                .session_params
+                .iter()
+                .any(|session_param| matches!(session_param, SessionParam::Other(_)))
+    })
+}
+
+impl<'a> Media<'a> {
+    /// Well-formed `a=crypto` attributes in order of appearance
+    pub fn cryptos(&self) -> Vec<Crypto<'a>> {
+        self.attributes_named(b"crypto")
+            .filter_map(|attribute| Crypto::parse(attribute.value?))
+            .collect()
+    }
+}
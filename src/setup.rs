@@ -0,0 +1,201 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::Media;
+
+/// `a=setup` of RFC 4145 section 4
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Setup {
+    Active,
+    Passive,
+    ActPass,
+    HoldConn,
+}
+
+impl Setup {
+    pub fn from_bytes(value: &[u8]) -> Option<Setup> {
+        match value {
+            b"active" => Some(Setup::Active),
+            b"passive" => Some(Setup::Passive),
+            b"actpass" => Some(Setup::ActPass),
+            b"holdconn" => Some(Setup::HoldConn),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Setup::Active => b"active",
+            Setup::Passive => b"passive",
+            Setup::ActPass => b"actpass",
+            Setup::HoldConn => b"holdconn",
+        }
+    }
+}
+
+impl fmt::Display for Setup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+/// `a=connection` of RFC 4145 section 5
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TcpConnection {
+    New,
+    Existing,
+}
+
+impl TcpConnection {
+    pub fn from_bytes(value: &[u8]) -> Option<TcpConnection> {
+        match value {
+            b"new" => Some(TcpConnection::New),
+            b"existing" => Some(TcpConnection::Existing),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            TcpConnection::New => b"new",
+            TcpConnection::Existing => b"existing",
+        }
+    }
+}
+
+impl fmt::Display for TcpConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+/// What the offerer does about the TCP connection once the answer is in
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TcpRole {
+    /// Opens the connection towards the answerer
+    Connect,
+    /// Waits for the answerer to connect
+    Accept,
+    /// Neither end opens a connection for now
+    Hold,
+}
+
+/// Outcome of `negotiate()`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TcpNegotiation {
+    /// Role of the offerer, the answerer takes the opposite one
+    pub role: TcpRole,
+    /// Whether the connection already in place is kept
+    pub reuse_existing: bool,
+}
+
+/// Returned when an answer does not fit the offer under RFC 4145
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SetupError {
+    /// `a=setup` or `a=connection` with an unknown value, `true` for the answer
+    BadValue(bool),
+    /// `actpass` is only meaningful in an offer
+    ActPassInAnswer,
+    /// Both ends claim the same role, or `holdconn` is not mirrored
+    Conflict(Setup, Setup),
+    /// `connection:existing` answered to an offer asking for a new connection
+    ExistingWithoutOffer,
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetupError::BadValue(true) => f.write_str("Bad setup or connection in answer"),
+            SetupError::BadValue(false) => f.write_str("Bad setup or connection in offer"),
+            SetupError::ActPassInAnswer => f.write_str("actpass in answer"),
+            SetupError::Conflict(offer, answer) => {
+                write!(f, "setup:{} cannot answer setup:{}", answer, offer)
+            }
+            SetupError::ExistingWithoutOffer => {
+                f.write_str("connection:existing in answer to connection:new")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SetupError {}
+
+impl<'a> Media<'a> {
+    /// `a=setup`, `None` when absent or unknown
+    pub fn setup(&self) -> Option<Setup> {
+        Setup::from_bytes(self.attribute(b"setup")?.value?)
+    }
+
+    /// `a=connection`, `None` when absent or unknown
+    pub fn tcp_connection(&self) -> Option<TcpConnection> {
+        TcpConnection::from_bytes(self.attribute(b"connection")?.value?)
+    }
+
+    fn setup_or_default(&self, is_answer: bool) -> Result<Setup, SetupError> {
+        match self.attribute(b"setup") {
+            Some(attribute) => attribute
+                .value
+                .and_then(Setup::from_bytes)
+                .ok_or(SetupError::BadValue(is_answer)),
+            None if is_answer => Ok(Setup::Passive),
+            None => Ok(Setup::Active),
+        }
+    }
+
+    fn tcp_connection_or_default(&self, is_answer: bool) -> Result<TcpConnection, SetupError> {
+        match self.attribute(b"connection") {
+            Some(attribute) => attribute
+                .value
+                .and_then(TcpConnection::from_bytes)
+                .ok_or(SetupError::BadValue(is_answer)),
+            None => Ok(TcpConnection::New),
+        }
+    }
+}
+
+/// Works out the TCP connection from an offer of ours and the remote answer.
+///
+/// An absent `a=setup` means `active` in the offer and `passive` in the
+/// answer, an absent `a=connection` means `new` (RFC 4145 sections 4
+/// and 5). The existing connection is only reused when both sides agree
+/// to it.
+pub fn negotiate(offer: &Media, answer: &Media) -> Result<TcpNegotiation, SetupError> {
+    let offer_setup = offer.setup_or_default(false)?;
+    let answer_setup = answer.setup_or_default(true)?;
+    let offer_connection = offer.tcp_connection_or_default(false)?;
+    let answer_connection = answer.tcp_connection_or_default(true)?;
+
+    let role = match (offer_setup, answer_setup) {
+        (_, Setup::ActPass) => return Err(SetupError::ActPassInAnswer),
+        (_, Setup::HoldConn) => TcpRole::Hold,
+        (Setup::Active | Setup::ActPass, Setup::Passive) => TcpRole::Connect,
+        (Setup::Passive | Setup::ActPass, Setup::Active) => TcpRole::Accept,
+        (offer_setup, answer_setup) => return Err(SetupError::Conflict(offer_setup, answer_setup)),
+    };
+
+    let reuse_existing = match (offer_connection, answer_connection) {
+        (TcpConnection::New, TcpConnection::Existing) => {
+            return Err(SetupError::ExistingWithoutOffer)
+        }
+        (TcpConnection::Existing, TcpConnection::Existing) => true,
+        _ => false,
+    };
+
+    Ok(TcpNegotiation {
+        role,
+        reuse_existing,
+    })
+}
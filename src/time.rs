@@ -0,0 +1,440 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt, io,
+    ops::Bound,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{parse_number, ToInt};
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET: u64 = 2208988800;
+
+/// Returned when a `SystemTime` cannot be written as an NTP time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NtpTimeOutOfRange;
+
+impl fmt::Display for NtpTimeOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Time out of NTP range")
+    }
+}
+
+impl std::error::Error for NtpTimeOutOfRange {}
+
+/// A start or stop time of a `t=` line
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NtpTime {
+    /// Written as `0`, the session is unbounded or permanent
+    Unbounded,
+    /// Seconds since 1900-01-01 00:00:00 UTC, never 0
+    Seconds(u64),
+}
+
+impl NtpTime {
+    pub fn from_ntp(seconds: u64) -> NtpTime {
+        if seconds == 0 {
+            NtpTime::Unbounded
+        } else {
+            NtpTime::Seconds(seconds)
+        }
+    }
+
+    /// The value as written on the wire
+    pub fn to_ntp(&self) -> u64 {
+        match self {
+            NtpTime::Unbounded => 0,
+            NtpTime::Seconds(seconds) => *seconds,
+        }
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        matches!(self, NtpTime::Unbounded)
+    }
+
+    /// `None` when unbounded or not representable on this platform
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        match self {
+            NtpTime::Unbounded => None,
+            NtpTime::Seconds(seconds) => {
+                if *seconds >= NTP_UNIX_OFFSET {
+                    UNIX_EPOCH.checked_add(Duration::from_secs(seconds - NTP_UNIX_OFFSET))
+                } else {
+                    UNIX_EPOCH.checked_sub(Duration::from_secs(NTP_UNIX_OFFSET - seconds))
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<SystemTime> for NtpTime {
+    type Error = NtpTimeOutOfRange;
+
+    /// Sub-second precision is truncated towards the NTP epoch
+    fn try_from(time: SystemTime) -> Result<NtpTime, NtpTimeOutOfRange> {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => NTP_UNIX_OFFSET.checked_add(since.as_secs()),
+            Err(e) => {
+                let before = e.duration();
+                let before = before.as_secs() + u64::from(before.subsec_nanos() > 0);
+                NTP_UNIX_OFFSET.checked_sub(before)
+            }
+        };
+
+        match seconds {
+            Some(seconds) if seconds > 0 => Ok(NtpTime::Seconds(seconds)),
+            _ => Err(NtpTimeOutOfRange),
+        }
+    }
+}
+
+/// Parses `<number>[d|h|m|s]` into seconds
+pub(crate) fn parse_typed_time(value: &[u8]) -> Option<u64> {
+    let (number, unit) = match value.last() {
+        Some(b'd') => (&value[..value.len() - 1], 86400),
+        Some(b'h') => (&value[..value.len() - 1], 3600),
+        Some(b'm') => (&value[..value.len() - 1], 60),
+        Some(b's') => (&value[..value.len() - 1], 1),
+        _ => (value, 1),
+    };
+
+    parse_number::<u64>(number)?.checked_mul(unit)
+}
+
+/// Writes seconds using the largest unit that divides them evenly
+pub(crate) fn write_typed_time<W: io::Write>(w: &mut W, seconds: u64) -> io::Result<()> {
+    if seconds == 0 {
+        write!(w, "0")
+    } else if seconds % 86400 == 0 {
+        write!(w, "{}d", seconds / 86400)
+    } else if seconds % 3600 == 0 {
+        write!(w, "{}h", seconds / 3600)
+    } else if seconds % 60 == 0 {
+        write!(w, "{}m", seconds / 60)
+    } else {
+        write!(w, "{}", seconds)
+    }
+}
+
+/// Parses `[-]<number>[d|h|m|s]` into seconds
+pub(crate) fn parse_signed_typed_time(value: &[u8]) -> Option<i64> {
+    match value.strip_prefix(b"-") {
+        Some(value) => i64::try_from(parse_typed_time(value)?).ok()?.checked_neg(),
+        None => i64::try_from(parse_typed_time(value)?).ok(),
+    }
+}
+
+pub(crate) fn write_signed_typed_time<W: io::Write>(w: &mut W, seconds: i64) -> io::Result<()> {
+    if seconds < 0 {
+        w.write_all(b"-")?;
+    }
+    write_typed_time(w, seconds.unsigned_abs())
+}
+
+/// Value of an `r=` line, all in seconds
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RepeatTime {
+    pub interval: u64,
+    pub active_duration: u64,
+    pub offsets: Vec<u64>,
+}
+
+impl RepeatTime {
+    pub(crate) fn parse(value: &[u8]) -> Option<RepeatTime> {
+        let mut iter = value
+            .split(|c| *c == b' ')
+            .filter(|field| !field.is_empty());
+
+        let interval = parse_typed_time(iter.next()?)?;
+        let active_duration = parse_typed_time(iter.next()?)?;
+
+        let mut offsets = Vec::new();
+        for offset in iter {
+            offsets.push(parse_typed_time(offset)?);
+        }
+
+        if offsets.is_empty() {
+            return None;
+        }
+
+        Some(RepeatTime {
+            interval,
+            active_duration,
+            offsets,
+        })
+    }
+
+    /// Writes the `r=` line, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(b"r=")?;
+        write_typed_time(w, self.interval)?;
+        w.write_all(b" ")?;
+        write_typed_time(w, self.active_duration)?;
+        for offset in &self.offsets {
+            w.write_all(b" ")?;
+            write_typed_time(w, *offset)?;
+        }
+        w.write_all(b"\r\n")
+    }
+}
+
+/// One adjustment of a `z=` line.
+///
+/// From `at` onwards, repeat times are computed from the base time shifted
+/// by `offset` seconds. Offsets are relative to the original base time, not
+/// to the previous adjustment.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TimeZoneAdjustment {
+    pub at: NtpTime,
+    pub offset: i64,
+}
+
+impl TimeZoneAdjustment {
+    pub(crate) fn parse_list(value: &[u8]) -> Option<Vec<TimeZoneAdjustment>> {
+        let mut iter = value
+            .split(|c| *c == b' ')
+            .filter(|field| !field.is_empty());
+
+        let mut adjustments = Vec::new();
+        while let Some(at) = iter.next() {
+            let at = NtpTime::from_ntp(parse_number::<u64>(at)?);
+            let offset = parse_signed_typed_time(iter.next()?)?;
+            adjustments.push(TimeZoneAdjustment { at, offset });
+        }
+
+        if adjustments.is_empty() {
+            return None;
+        }
+
+        Some(adjustments)
+    }
+
+    /// Writes a `z=` line holding all `adjustments`, CRLF terminated
+    pub fn write_list_to<W: io::Write>(
+        adjustments: &[TimeZoneAdjustment],
+        w: &mut W,
+    ) -> io::Result<()> {
+        w.write_all(b"z=")?;
+        for (idx, adjustment) in adjustments.iter().enumerate() {
+            if idx > 0 {
+                w.write_all(b" ")?;
+            }
+            write!(w, "{} ", adjustment.at.to_ntp())?;
+            write_signed_typed_time(w, adjustment.offset)?;
+        }
+        w.write_all(b"\r\n")
+    }
+
+    /// The offset in effect at `time`, in NTP seconds
+    pub fn offset_at(adjustments: &[TimeZoneAdjustment], time: u64) -> i64 {
+        adjustments
+            .iter()
+            .filter(|adjustment| adjustment.at.to_ntp() <= time)
+            .max_by_key(|adjustment| adjustment.at.to_ntp())
+            .map_or(0, |adjustment| adjustment.offset)
+    }
+}
+
+/// A `t=` line and the `r=` lines following it
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TimeDescription {
+    pub start_time: NtpTime,
+    pub stop_time: NtpTime,
+    pub repeats: Vec<RepeatTime>,
+}
+
+impl TimeDescription {
+    /// `None` on bad format, `Some(Err)` on values out of range
+    pub(crate) fn parse(
+        start_time: &[u8],
+        stop_time: &[u8],
+    ) -> Option<Result<TimeDescription, NtpTimeOutOfRange>> {
+        if start_time.is_empty()
+            || stop_time.is_empty()
+            || !start_time.iter().chain(stop_time).all(u8::is_ascii_digit)
+        {
+            return None;
+        }
+
+        let (start_time, stop_time) = match (start_time.to_int::<u64>(), stop_time.to_int::<u64>())
+        {
+            (Ok(start_time), Ok(stop_time)) => {
+                (NtpTime::from_ntp(start_time), NtpTime::from_ntp(stop_time))
+            }
+            _ => return Some(Err(NtpTimeOutOfRange)),
+        };
+
+        if let (NtpTime::Seconds(start), NtpTime::Seconds(stop)) = (start_time, stop_time) {
+            if stop < start {
+                return Some(Err(NtpTimeOutOfRange));
+            }
+        }
+
+        Some(Ok(TimeDescription {
+            start_time,
+            stop_time,
+            repeats: Vec::new(),
+        }))
+    }
+
+    /// Writes the `t=` line and its `r=` lines, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(
+            w,
+            "t={} {}\r\n",
+            self.start_time.to_ntp(),
+            self.stop_time.to_ntp()
+        )?;
+
+        for repeat in &self.repeats {
+            repeat.write_to(w)?;
+        }
+
+        Ok(())
+    }
+
+    /// Lists the intervals during which the session is active, in chronological order.
+    ///
+    /// Without `r=` lines this is the single `t=` interval. The iterator is
+    /// endless when repeats are given for a session with no stop time.
+    pub fn occurrences(&self) -> Occurrences<'_> {
+        self.adjusted_occurrences(&[])
+    }
+
+    /// Same as `occurrences()`, with repeat times shifted according to the `z=` adjustments
+    pub fn adjusted_occurrences<'t>(
+        &'t self,
+        adjustments: &'t [TimeZoneAdjustment],
+    ) -> Occurrences<'t> {
+        let mut pending = Vec::new();
+        if !self.start_time.is_unbounded() {
+            for repeat in &self.repeats {
+                for offset in &repeat.offsets {
+                    pending.push(Pending {
+                        interval: repeat.interval,
+                        active_duration: repeat.active_duration,
+                        offset: *offset,
+                        count: 0,
+                    });
+                }
+            }
+        }
+
+        Occurrences {
+            time_description: self,
+            adjustments,
+            single: pending.is_empty(),
+            pending,
+        }
+    }
+}
+
+impl fmt::Display for TimeDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+struct Pending {
+    interval: u64,
+    active_duration: u64,
+    offset: u64,
+    count: u64,
+}
+
+impl Pending {
+    fn start(&self, start_time: u64) -> Option<u64> {
+        if self.interval == 0 && self.count > 0 {
+            return None;
+        }
+        self.interval
+            .checked_mul(self.count)?
+            .checked_add(self.offset)?
+            .checked_add(start_time)
+    }
+}
+
+fn start_bound(time: NtpTime) -> Option<Bound<SystemTime>> {
+    match time {
+        NtpTime::Unbounded => Some(Bound::Unbounded),
+        NtpTime::Seconds(_) => Some(Bound::Included(time.to_system_time()?)),
+    }
+}
+
+fn end_bound(time: NtpTime) -> Option<Bound<SystemTime>> {
+    match time {
+        NtpTime::Unbounded => Some(Bound::Unbounded),
+        NtpTime::Seconds(_) => Some(Bound::Excluded(time.to_system_time()?)),
+    }
+}
+
+/// Iterator returned by `TimeDescription::occurrences()`
+pub struct Occurrences<'t> {
+    time_description: &'t TimeDescription,
+    adjustments: &'t [TimeZoneAdjustment],
+    single: bool,
+    pending: Vec<Pending>,
+}
+
+impl<'t> Iterator for Occurrences<'t> {
+    type Item = (Bound<SystemTime>, Bound<SystemTime>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start_time = self.time_description.start_time;
+        let stop_time = self.time_description.stop_time;
+
+        if self.single {
+            self.single = false;
+            return Some((start_bound(start_time)?, end_bound(stop_time)?));
+        }
+
+        let start_time = start_time.to_ntp();
+        let stop_time = match stop_time {
+            NtpTime::Unbounded => u64::MAX,
+            NtpTime::Seconds(seconds) => seconds,
+        };
+
+        let (idx, start) = self
+            .pending
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, pending)| pending.start(start_time).map(|start| (idx, start)))
+            .filter(|(_, start)| *start < stop_time)
+            .min_by_key(|(_, start)| *start)?;
+
+        let pending = &mut self.pending[idx];
+        pending.count += 1;
+
+        let end = start.saturating_add(pending.active_duration).min(stop_time);
+
+        let offset = TimeZoneAdjustment::offset_at(self.adjustments, start);
+        let start = start.saturating_add_signed(offset);
+        let end = if end == u64::MAX {
+            end
+        } else {
+            end.saturating_add_signed(offset)
+        };
+        let end = if end == u64::MAX {
+            NtpTime::Unbounded
+        } else {
+            NtpTime::Seconds(end)
+        };
+
+        Some((start_bound(NtpTime::Seconds(start))?, end_bound(end)?))
+    }
+}
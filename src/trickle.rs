@@ -0,0 +1,267 @@
+// Copyright 2023 宋昊文
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt, io};
+
+use crate::{
+    ice::{candidates_of, IceCandidate},
+    Attribute, ErrorLocation, Media, Sdp, SdpError, ToInt,
+};
+
+/// Body of an `application/trickle-ice-sdpfrag` message, RFC 8840 section 9.
+///
+/// There is no `v=`, `o=`, `s=` or `t=`, only session level attributes
+/// followed by `m=` sections identified by their `a=mid`. Media sections
+/// carry no connection data, bandwidth or key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SdpFragment<'a> {
+    pub attributes: Vec<Attribute<'a>>,
+    pub medias: Vec<Media<'a>>,
+}
+
+fn parse_media_line(value: &[u8]) -> Option<Media<'_>> {
+    let mut iter = value.split(|c| *c == b' ');
+    let media_type = iter.next().filter(|media_type| !media_type.is_empty())?;
+    let port_pair = iter.next()?;
+    let (port, number_of_ports) = match port_pair.iter().position(|c| *c == b'/') {
+        Some(idx) => (
+            port_pair[..idx].to_int::<u16>().ok()?,
+            port_pair[idx + 1..].to_int::<i32>().ok()?,
+        ),
+        None => (port_pair.to_int::<u16>().ok()?, 1),
+    };
+    let protocol = iter.next().filter(|protocol| !protocol.is_empty())?;
+    Some(Media {
+        media_type,
+        port,
+        number_of_ports,
+        protocol,
+        formats: iter.filter(|format| !format.is_empty()).collect(),
+        title: None,
+        connection: None,
+        bandwidths: Vec::new(),
+        encryption_key: None,
+        attributes: Vec::new(),
+    })
+}
+
+impl<'a> SdpFragment<'a> {
+    /// Parses a fragment, lines other than `a=` and `m=` are skipped
+    pub fn parse(buf: &'a [u8]) -> Result<SdpFragment<'a>, SdpError> {
+        let mut attributes = Vec::new();
+        let mut medias: Vec<Media<'a>> = Vec::new();
+
+        let mut line_start = 0;
+        while line_start < buf.len() {
+            let line_end = buf[line_start..]
+                .iter()
+                .position(|c| *c == b'\n')
+                .map_or(buf.len(), |idx| line_start + idx);
+            let next_line_start = line_end + 1;
+            let line = &buf[line_start..line_end];
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+            if !line.is_empty() {
+                if line.len() < 2 || line[1] != b'=' {
+                    return Err(SdpError::BadLine(ErrorLocation::locate(buf, line_start)));
+                }
+                let value = &line[2..];
+                match line[0] {
+                    b'a' if !value.is_empty() => {
+                        let attribute = Attribute::parse(value);
+                        match medias.last_mut() {
+                            Some(media) => media.attributes.push(attribute),
+                            None => attributes.push(attribute),
+                        }
+                    }
+                    b'm' => match parse_media_line(value) {
+                        Some(media) if !media.formats.is_empty() => medias.push(media),
+                        Some(_) => {
+                            return Err(SdpError::BadMedia(ErrorLocation::locate(buf, line_start)))
+                        }
+                        None => {
+                            return Err(SdpError::BadMediaPort(ErrorLocation::locate(
+                                buf, line_start,
+                            )))
+                        }
+                    },
+                    _ => {}
+                }
+            }
+
+            line_start = next_line_start;
+        }
+
+        Ok(SdpFragment { attributes, medias })
+    }
+
+    /// Writes the fragment back, CRLF terminated
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        for attribute in &self.attributes {
+            attribute.write_to(w)?;
+        }
+        for media in &self.medias {
+            media.write_to(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Debug for SdpFragment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = f.debug_struct("SdpFragment");
+        for attribute in &self.attributes {
+            debug_struct.field("attribute", attribute);
+        }
+        debug_struct.field("medias", &self.medias).finish()
+    }
+}
+
+impl<'a> fmt::Display for SdpFragment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Returned when a fragment does not fit the description it is applied to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FragmentError {
+    /// No media of the description has this `a=mid`
+    UnknownMid(String),
+    /// The fragment media at this index has no `a=mid` and there is no
+    /// media at the same index
+    UnknownIndex(usize),
+    /// The `a=ice-ufrag` of the fragment differs from the one of the media,
+    /// which means an ICE restart rather than trickled candidates
+    UfragMismatch(String),
+}
+
+impl fmt::Display for FragmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FragmentError::UnknownMid(mid) => write!(f, "No media with mid {}", mid),
+            FragmentError::UnknownIndex(idx) => write!(f, "No media at index {}", idx),
+            FragmentError::UfragMismatch(ufrag) => write!(f, "Unexpected ice-ufrag {}", ufrag),
+        }
+    }
+}
+
+impl std::error::Error for FragmentError {}
+
+impl<'a> Sdp<'a> {
+    /// Appends the candidates of `fragment` to the matching media.
+    ///
+    /// Media are matched by `a=mid`, or by position when the fragment media
+    /// has none. Candidates already present are not added twice, and
+    /// `a=end-of-candidates` is carried over, to every media when the
+    /// fragment has no `m=` section. Nothing is changed when an error is
+    /// returned.
+    pub fn apply_fragment(&mut self, fragment: &SdpFragment<'a>) -> Result<(), FragmentError> {
+        let fragment_ufrag = fragment
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == b"ice-ufrag")
+            .and_then(|attribute| attribute.value);
+        let fragment_end_of_candidates = fragment
+            .attributes
+            .iter()
+            .any(|attribute| attribute.name == b"end-of-candidates");
+
+        // Without any m= section the session level `a=end-of-candidates`
+        // concerns every media (RFC 8840)
+        if fragment.medias.is_empty() {
+            if let Some(ufrag) = fragment_ufrag {
+                if self.medias.iter().any(|media| {
+                    media
+                        .ice_ufrag(self)
+                        .is_some_and(|current| current != ufrag)
+                }) {
+                    return Err(FragmentError::UfragMismatch(
+                        String::from_utf8_lossy(ufrag).into_owned(),
+                    ));
+                }
+            }
+            if fragment_end_of_candidates {
+                for media in &mut self.medias {
+                    if !media.has_flag(b"end-of-candidates") {
+                        media.attributes.push(Attribute {
+                            name: b"end-of-candidates",
+                            value: None,
+                        });
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let mut targets = Vec::with_capacity(fragment.medias.len());
+        for (idx, fragment_media) in fragment.medias.iter().enumerate() {
+            let target = match fragment_media.mid() {
+                Some(mid) => self
+                    .medias
+                    .iter()
+                    .position(|media| media.mid() == Some(mid))
+                    .ok_or_else(|| {
+                        FragmentError::UnknownMid(String::from_utf8_lossy(mid).into_owned())
+                    })?,
+                None if idx < self.medias.len() => idx,
+                None => return Err(FragmentError::UnknownIndex(idx)),
+            };
+
+            let ufrag = fragment_media
+                .attribute(b"ice-ufrag")
+                .and_then(|attribute| attribute.value)
+                .or(fragment_ufrag);
+            if let Some(ufrag) = ufrag {
+                if let Some(current) = self.medias[target].ice_ufrag(self) {
+                    if current != ufrag {
+                        return Err(FragmentError::UfragMismatch(
+                            String::from_utf8_lossy(ufrag).into_owned(),
+                        ));
+                    }
+                }
+            }
+
+            targets.push(target);
+        }
+
+        for (fragment_media, target) in fragment.medias.iter().zip(targets) {
+            let media = &mut self.medias[target];
+            let mut existing = candidates_of(&media.attributes);
+            let mut end_of_candidates = fragment_end_of_candidates;
+            for attribute in &fragment_media.attributes {
+                if attribute.name == b"candidate" {
+                    if let Some(candidate) = attribute.value.and_then(IceCandidate::parse) {
+                        if !existing.contains(&candidate) {
+                            existing.push(candidate);
+                            media.attributes.push(attribute.clone());
+                        }
+                    }
+                } else if attribute.name == b"end-of-candidates" {
+                    end_of_candidates = true;
+                }
+            }
+            if end_of_candidates && !media.has_flag(b"end-of-candidates") {
+                media.attributes.push(Attribute {
+                    name: b"end-of-candidates",
+                    value: None,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
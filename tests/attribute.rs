@@ -0,0 +1,64 @@
+use rust_strict_sdp::{AsSDP, Attribute};
+
+const SDP: &[u8] = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+a=tool:libfoo 1.0\r\n\
+a=recvonly\r\n\
+m=audio 49170 RTP/AVP 0 8\r\n\
+a=rtpmap:0 PCMU/8000\r\n\
+a=rtpmap:8 PCMA/8000\r\n\
+a=mid:audio\r\n\
+a=ptime:\r\n\
+a=sendonly\r\n";
+
+#[test]
+fn splits_name_and_value_at_first_colon() {
+    assert_eq!(
+        Attribute::parse(b"fingerprint:sha-1 4A:AD"),
+        Attribute {
+            name: b"fingerprint",
+            value: Some(b"sha-1 4A:AD"),
+        }
+    );
+    assert_eq!(
+        Attribute::parse(b"recvonly"),
+        Attribute {
+            name: b"recvonly",
+            value: None,
+        }
+    );
+    assert_eq!(Attribute::parse(b"ptime:").value, Some(&b""[..]));
+}
+
+#[test]
+fn looks_up_attributes_by_name() {
+    let sdp = SDP.as_sdp().unwrap();
+
+    assert_eq!(
+        sdp.attribute(b"tool").and_then(|attribute| attribute.value),
+        Some(&b"libfoo 1.0"[..])
+    );
+    assert!(sdp.has_flag(b"recvonly"));
+    assert!(!sdp.has_flag(b"tool"));
+    assert_eq!(sdp.attributes_named(b"tool").count(), 1);
+
+    let media = &sdp.medias[0];
+    let rtpmaps: Vec<_> = media
+        .attributes_named(b"rtpmap")
+        .filter_map(|attribute| attribute.value)
+        .collect();
+    assert_eq!(rtpmaps, vec![&b"0 PCMU/8000"[..], b"8 PCMA/8000"]);
+    assert_eq!(media.mid(), Some(&b"audio"[..]));
+    assert!(media.has_flag(b"sendonly"));
+    assert!(!media.has_flag(b"ptime"));
+    assert!(media.attribute(b"framerate").is_none());
+}
+
+#[test]
+fn writes_attributes_back_unchanged() {
+    let sdp = SDP.as_sdp().unwrap();
+    assert_eq!(sdp.to_string().as_bytes(), SDP);
+    assert_eq!(sdp.medias[0].attributes[3].to_string(), "a=ptime:\r\n");
+}
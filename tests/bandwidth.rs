@@ -0,0 +1,82 @@
+use rust_strict_sdp::{AsSDP, BandwidthModifier, MediaBuilder, SdpBuilder, SdpError};
+
+#[test]
+fn parses_bandwidth_at_both_levels() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+b=CT:256\r\n\
+b=X-YZ:128\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+b=as:64\r\n\
+b=TIAS:5000000000\r\n\
+b=RS:800\r\n\
+b=RR:2000\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(sdp.bandwidths.len(), 2);
+    assert_eq!(sdp.bandwidths[0].modifier, BandwidthModifier::CT);
+    assert_eq!(sdp.bandwidths[0].value, 256);
+    assert_eq!(
+        sdp.bandwidths[1].modifier,
+        BandwidthModifier::Other(b"X-YZ")
+    );
+
+    let bandwidths = &sdp.medias[0].bandwidths;
+    assert_eq!(bandwidths[0].modifier, BandwidthModifier::AS);
+    assert_eq!(bandwidths[0].value, 64);
+    assert_eq!(bandwidths[1].modifier, BandwidthModifier::TIAS);
+    assert_eq!(bandwidths[1].value, 5_000_000_000);
+    assert_eq!(bandwidths[2].modifier, BandwidthModifier::RS);
+    assert_eq!(bandwidths[3].modifier, BandwidthModifier::RR);
+    assert_eq!(bandwidths[0].to_string(), "b=AS:64\r\n");
+}
+
+#[test]
+fn rejects_bad_bandwidth() {
+    for line in ["b=AS:4294967296", "b=CT:-1", "b=AS:", "b=:64", "b=AS64"] {
+        let sdp = format!(
+            "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\n{}\r\nt=0 0\r\n",
+            line
+        );
+        assert!(
+            matches!(sdp.as_bytes().as_sdp(), Err(SdpError::BadBandwidth(_))),
+            "{}",
+            line
+        );
+    }
+
+    let sdp = b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nb=AS:4294967295\r\nt=0 0\r\n";
+    assert_eq!(sdp.as_sdp().unwrap().bandwidths[0].value, 4294967295);
+}
+
+#[test]
+fn builder_checks_bandwidth_range() {
+    let result = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .bandwidth(BandwidthModifier::CT, u64::from(u32::MAX) + 1)
+        .build();
+    assert_eq!(result, Err(SdpError::InvalidField('b')));
+
+    let result = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .connection("192.0.2.1")
+        .media(
+            MediaBuilder::new("audio", 49170, "RTP/AVP")
+                .format("0")
+                .bandwidth(BandwidthModifier::RR, u64::MAX),
+        )
+        .build();
+    assert_eq!(result, Err(SdpError::InvalidField('b')));
+
+    let sdp = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .bandwidth(BandwidthModifier::TIAS, u64::MAX)
+        .bandwidth(BandwidthModifier::AS, u64::from(u32::MAX))
+        .build()
+        .unwrap();
+    let written = sdp.to_string();
+    assert_eq!(written.as_bytes().as_sdp().unwrap(), sdp);
+}
@@ -0,0 +1,72 @@
+use rust_strict_sdp::{AsSDP, MediaBuilder, SdpBuilder, SdpError};
+
+#[test]
+fn builds_minimal_session() {
+    let sdp = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .build()
+        .unwrap();
+    assert_eq!(
+        sdp.to_string(),
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n"
+    );
+}
+
+#[test]
+fn builds_session_with_media() {
+    let sdp = SdpBuilder::new()
+        .origin("alice", "2890844526", "2890844526", "2001:db8::1")
+        .session_name("Call")
+        .connection("2001:db8::1")
+        .attribute("sendrecv")
+        .media(
+            MediaBuilder::new("audio", 49170, "RTP/AVP")
+                .format("0")
+                .format("97")
+                .title("Voice")
+                .attribute("rtpmap:97 iLBC/8000"),
+        )
+        .media(
+            MediaBuilder::new("video", 51372, "RTP/AVP")
+                .format("31")
+                .connection("192.0.2.2"),
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        sdp.to_string(),
+        "v=0\r\n\
+         o=alice 2890844526 2890844526 IN IP6 2001:db8::1\r\n\
+         s=Call\r\n\
+         c=IN IP6 2001:db8::1\r\n\
+         t=0 0\r\n\
+         a=sendrecv\r\n\
+         m=audio 49170 RTP/AVP 0 97\r\n\
+         i=Voice\r\n\
+         a=rtpmap:97 iLBC/8000\r\n\
+         m=video 51372 RTP/AVP 31\r\n\
+         c=IN IP4 192.0.2.2\r\n"
+    );
+
+    let written = sdp.to_string();
+    assert_eq!(written.as_bytes().as_sdp().unwrap(), sdp);
+}
+
+#[test]
+fn requires_mandatory_fields() {
+    assert_eq!(SdpBuilder::new().build(), Err(SdpError::MissingField('o')));
+
+    let result = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .connection("192.0.2.1")
+        .media(MediaBuilder::new("audio", 49170, "RTP/AVP"))
+        .build();
+    assert_eq!(result, Err(SdpError::MissingField('m')));
+
+    let result = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .media(MediaBuilder::new("audio", 49170, "RTP/AVP").format("0"))
+        .build();
+    assert_eq!(result, Err(SdpError::MissingField('c')));
+}
@@ -0,0 +1,90 @@
+use rust_strict_sdp::{
+    direction::{Direction, LegacyHold},
+    AsSDP,
+};
+
+#[test]
+fn inherits_session_level_direction() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+a=recvonly\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+m=video 51372 RTP/AVP 31\r\n\
+a=inactive\r\n\
+m=text 0 RTP/AVP 98\r\n\
+a=sendrecv\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(sdp.direction(), Some(Direction::RecvOnly));
+    assert_eq!(sdp.medias[0].direction(), None);
+    assert_eq!(sdp.medias[0].effective_direction(&sdp), Direction::RecvOnly);
+    assert_eq!(sdp.medias[1].effective_direction(&sdp), Direction::Inactive);
+    assert_eq!(sdp.medias[2].effective_direction(&sdp), Direction::Inactive);
+}
+
+#[test]
+fn defaults_to_sendrecv() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(sdp.direction(), None);
+    assert_eq!(sdp.medias[0].effective_direction(&sdp), Direction::SendRecv);
+}
+
+#[test]
+fn applies_legacy_hold_setting() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 0.0.0.0\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+m=video 51372 RTP/AVP 31\r\n\
+a=recvonly\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+    let audio = &sdp.medias[0];
+    let video = &sdp.medias[1];
+
+    assert_eq!(audio.effective_direction(&sdp), Direction::SendOnly);
+    assert_eq!(video.effective_direction(&sdp), Direction::Inactive);
+
+    assert_eq!(
+        audio.effective_direction_with(&sdp, LegacyHold::Ignore),
+        Direction::SendRecv
+    );
+    assert_eq!(
+        video.effective_direction_with(&sdp, LegacyHold::Ignore),
+        Direction::RecvOnly
+    );
+    assert_eq!(
+        audio.effective_direction_with(&sdp, LegacyHold::Inactive),
+        Direction::Inactive
+    );
+}
+
+#[test]
+fn converts_directions() {
+    for direction in [
+        Direction::SendRecv,
+        Direction::SendOnly,
+        Direction::RecvOnly,
+        Direction::Inactive,
+    ] {
+        assert_eq!(Direction::from_bytes(direction.as_bytes()), Some(direction));
+    }
+    assert_eq!(Direction::from_bytes(b"sendRecv"), None);
+
+    assert_eq!(Direction::SendOnly.reversed(), Direction::RecvOnly);
+    assert_eq!(Direction::SendRecv.reversed(), Direction::SendRecv);
+    assert!(Direction::SendOnly.is_sending());
+    assert!(!Direction::SendOnly.is_receiving());
+    assert!(!Direction::Inactive.is_sending());
+}
@@ -0,0 +1,138 @@
+use rust_strict_sdp::{
+    dtls::{Fingerprint, FingerprintError, HashFunction},
+    AsSDP,
+};
+
+const SHA_256: &str = "4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:AB:3E:4B:65:C9:41:47:2D:7E:E2:4E:9E:00";
+
+fn session(session_attributes: &str, media_attributes: &str) -> String {
+    format!(
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nc=IN IP4 192.0.2.1\r\nt=0 0\r\n{}m=audio 49170 UDP/TLS/RTP/SAVP 0\r\n{}",
+        session_attributes, media_attributes
+    )
+}
+
+/// Stands in for a real digest, only sha-256 is "implemented"
+fn fake_sha_256(hash_function: HashFunction, certificate: &[u8]) -> Option<Vec<u8>> {
+    match hash_function {
+        HashFunction::Sha256 => Some(certificate.iter().cycle().take(32).copied().collect()),
+        _ => None,
+    }
+}
+
+#[test]
+fn parses_fingerprints() {
+    let value = format!("SHA-256 {}", SHA_256);
+    let fingerprint = Fingerprint::parse(value.as_bytes()).unwrap();
+    assert_eq!(fingerprint.hash_function, HashFunction::Sha256);
+    assert_eq!(fingerprint.digest.len(), 32);
+    assert_eq!(fingerprint.digest[0], 0x4a);
+    assert_eq!(fingerprint.digest[31], 0x00);
+
+    let fingerprint = Fingerprint::parse(b"x-hash 01:02:03").unwrap();
+    assert_eq!(fingerprint.hash_function, HashFunction::Other(b"x-hash"));
+    assert_eq!(fingerprint.hash_function.digest_len(), None);
+    assert_eq!(fingerprint.digest, vec![1, 2, 3]);
+
+    for value in [
+        &b"sha-256 4A:AD"[..],
+        b"sha-256",
+        b" 4A:AD",
+        b"sha-1 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:ZZ",
+        b"x-hash 4AAD",
+    ] {
+        assert!(
+            Fingerprint::parse(value).is_none(),
+            "{}",
+            String::from_utf8_lossy(value)
+        );
+    }
+}
+
+#[test]
+fn writes_fingerprints() {
+    let value = format!("sha-256 {}", SHA_256.to_ascii_lowercase());
+    let fingerprint = Fingerprint::parse(value.as_bytes()).unwrap();
+    assert_eq!(
+        fingerprint.to_string(),
+        format!("a=fingerprint:sha-256 {}\r\n", SHA_256)
+    );
+}
+
+#[test]
+fn falls_back_to_session_fingerprints() {
+    let sdp = session(
+        &format!("a=fingerprint:sha-256 {}\r\n", SHA_256),
+        "a=fingerprint:sha-1 broken\r\n",
+    );
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.fingerprints().len(), 1);
+    assert_eq!(sdp.medias[0].fingerprints(&sdp), sdp.fingerprints());
+
+    let sdp = session(
+        &format!("a=fingerprint:sha-256 {}\r\n", SHA_256),
+        "a=fingerprint:md5 00:01:02:03:04:05:06:07:08:09:0A:0B:0C:0D:0E:0F\r\n",
+    );
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    let fingerprints = sdp.medias[0].fingerprints(&sdp);
+    assert_eq!(fingerprints.len(), 1);
+    assert_eq!(fingerprints[0].hash_function, HashFunction::Md5);
+}
+
+#[test]
+fn validates_tls_id() {
+    let sdp = session(
+        "a=tls-id:abc123+/-_abc123+/-_\r\n",
+        "a=tls-id:too-short\r\n",
+    );
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(
+        sdp.medias[0].tls_id(&sdp),
+        Some(&b"abc123+/-_abc123+/-_"[..])
+    );
+
+    let sdp = session("", "a=tls-id:abcdefghij!klmnopqrstu\r\n");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.medias[0].tls_id(&sdp), None);
+}
+
+#[test]
+fn verifies_certificates() {
+    let certificate = [0x4a, 0x4b];
+    let digest: Vec<String> = certificate
+        .iter()
+        .cycle()
+        .take(32)
+        .map(|c| format!("{:02X}", c))
+        .collect();
+    let sdp = session(
+        "",
+        &format!(
+            "a=fingerprint:sha-1 00:01:02:03:04:05:06:07:08:09:0A:0B:0C:0D:0E:0F:10:11:12:13\r\n\
+a=fingerprint:sha-256 {}\r\n",
+            digest.join(":")
+        ),
+    );
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    let media = &sdp.medias[0];
+
+    assert_eq!(
+        media.verify_certificate(&sdp, &certificate, fake_sha_256),
+        Ok(())
+    );
+    assert_eq!(
+        media.verify_certificate(&sdp, &[0x00], fake_sha_256),
+        Err(FingerprintError::Mismatch)
+    );
+    assert_eq!(
+        media.verify_certificate(&sdp, &certificate, |_, _| None),
+        Err(FingerprintError::UnsupportedHashFunction)
+    );
+
+    let sdp = session("", "");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(
+        sdp.medias[0].verify_certificate(&sdp, &certificate, fake_sha_256),
+        Err(FingerprintError::NoFingerprint)
+    );
+}
@@ -0,0 +1,204 @@
+use rust_strict_sdp::{AsSDP, ErrorLocation, SdpError};
+
+fn session(body: &str) -> String {
+    format!(
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\n{}",
+        body.replace('\n', "\r\n")
+    )
+}
+
+fn parse_error(sdp: &str) -> SdpError {
+    sdp.as_bytes().as_sdp().unwrap_err()
+}
+
+fn error_line(sdp: &str) -> String {
+    parse_error(sdp).location().unwrap().line.clone()
+}
+
+#[test]
+fn accepts_minimal_session() {
+    let sdp = session("t=0 0\n");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.times.len(), 1);
+    assert!(sdp.medias.is_empty());
+}
+
+#[test]
+fn reports_bad_field_values() {
+    let sdp = "v=0 1\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n";
+    assert!(matches!(parse_error(sdp), SdpError::BadVersion(_)));
+
+    let sdp = "v=0\r\no=- 1 1 IN IP4\r\ns=-\r\nt=0 0\r\n";
+    assert!(matches!(parse_error(sdp), SdpError::BadOrigin(_)));
+
+    let sdp = "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=\r\nt=0 0\r\n";
+    assert!(matches!(parse_error(sdp), SdpError::BadSessionName(_)));
+
+    let sdp = session("c=IN IP4\nt=0 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadConnection(_)));
+
+    let sdp = session("b=AS:fast\nt=0 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadBandwidth(_)));
+
+    let sdp = session("t=0 0\nm=audio port RTP/AVP 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadMediaPort(_)));
+
+    let sdp = session("t=0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadTime(_)));
+
+    let sdp = session("t=0 0 5\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadTime(_)));
+
+    let sdp = session("t=3034423620 3034423619\n");
+    assert!(matches!(parse_error(&sdp), SdpError::TimeOutOfRange(_)));
+
+    let sdp = session("t=3034423619 3042462419\nr=weekly 1h 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadRepeat(_)));
+
+    let sdp = session("t=0 0\nz=2882844526\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadTimeZone(_)));
+
+    let sdp = session("t=0 0\nk=base64:***\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadKey(_)));
+}
+
+#[test]
+fn accepts_last_line_without_line_break() {
+    let sdp = session("t=0 0");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.times.len(), 1);
+
+    let sdp = session("t=0 0\nm=audio 49170 RTP/AVP 0\na=recvonly");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.medias.len(), 1);
+    assert_eq!(sdp.medias[0].attributes.len(), 1);
+    assert_eq!(sdp.medias[0].attributes[0].name, b"recvonly");
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    let sdp = session("c IN IP4 192.0.2.1\nt=0 0\n");
+    let error = parse_error(&sdp);
+    assert!(matches!(error, SdpError::BadLine(_)));
+    assert_eq!(error_line(&sdp), "c IN IP4 192.0.2.1");
+    assert_eq!(
+        error.to_string(),
+        "Malformed line at line 4 (offset 36): c IN IP4 192.0.2.1"
+    );
+
+    let sdp = session("cx\nc=IN IP4 192.0.2.1\nt=0 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::BadLine(_)));
+    assert_eq!(error_line(&sdp), "cx");
+
+    let sdp = session("t=0 0\na");
+    assert!(matches!(parse_error(&sdp), SdpError::BadLine(_)));
+
+    let sdp = session("x=unknown\nt=0 0\n");
+    assert!(sdp.as_bytes().as_sdp().is_ok());
+}
+
+#[test]
+fn reports_missing_fields() {
+    let sdp = "o=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n";
+    assert_eq!(parse_error(sdp), SdpError::MissingField('v'));
+
+    let sdp = "v=0\r\ns=-\r\nt=0 0\r\n";
+    assert_eq!(parse_error(sdp), SdpError::MissingField('o'));
+
+    let sdp = "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\nt=0 0\r\n";
+    assert_eq!(parse_error(sdp), SdpError::MissingField('s'));
+
+    let sdp = session("");
+    assert_eq!(parse_error(&sdp), SdpError::MissingField('t'));
+
+    let sdp = session("a=recvonly\nm=audio 49170 RTP/AVP 0\n");
+    let error = parse_error(&sdp);
+    assert_eq!(error, SdpError::MissingField('t'));
+    assert!(error.location().is_none());
+}
+
+#[test]
+fn rejects_duplicated_fields() {
+    let sdp = "v=0\r\nv=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n";
+    assert!(matches!(parse_error(sdp), SdpError::Duplicated(_)));
+
+    let sdp = session("t=0 0\nz=2882844526 -1h\nz=2898848070 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::Duplicated(_)));
+    assert_eq!(error_line(&sdp), "z=2898848070 0");
+
+    let sdp = session("t=0 0\nk=prompt\nk=clear:secret\n");
+    assert!(matches!(parse_error(&sdp), SdpError::Duplicated(_)));
+    assert_eq!(error_line(&sdp), "k=clear:secret");
+}
+
+#[test]
+fn rejects_out_of_order_fields() {
+    let sdp = "v=0\r\ns=-\r\no=- 1 1 IN IP4 192.0.2.1\r\nt=0 0\r\n";
+    assert!(matches!(parse_error(sdp), SdpError::OutOfOrder(_)));
+
+    let sdp = session("t=0 0\nc=IN IP4 192.0.2.1\n");
+    assert!(matches!(parse_error(&sdp), SdpError::OutOfOrder(_)));
+
+    let sdp = session("r=7d 1h 0\nt=0 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::OutOfOrder(_)));
+
+    let sdp = session("t=0 0\nm=audio 49170 RTP/AVP 0\ns=-\n");
+    assert!(matches!(parse_error(&sdp), SdpError::OutOfOrder(_)));
+}
+
+#[test]
+fn keeps_time_section_grammar() {
+    let sdp = session(
+        "t=3034423619 3042462419\n\
+         r=7d 1h 0 25h\n\
+         r=7d 1h 0\n\
+         t=3034423619 3042462419\n\
+         z=2882844526 -1h 2898848070 0\n\
+         k=prompt\n\
+         a=recvonly\n\
+         a=tool:test\n",
+    );
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.times.len(), 2);
+    assert_eq!(sdp.times[0].repeats.len(), 2);
+    assert_eq!(sdp.time_zone_adjustments.len(), 2);
+    assert_eq!(sdp.attributes.len(), 2);
+
+    let sdp = session("t=0 0\na=recvonly\nt=0 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::OutOfOrder(_)));
+    assert_eq!(error_line(&sdp), "t=0 0");
+
+    let sdp = session("t=0 0\nz=2882844526 -1h\nr=7d 1h 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::OutOfOrder(_)));
+    assert_eq!(error_line(&sdp), "r=7d 1h 0");
+
+    let sdp = session("t=0 0\na=recvonly\nr=7d 1h 0\n");
+    assert!(matches!(parse_error(&sdp), SdpError::OutOfOrder(_)));
+
+    let sdp = session("t=0 0\nk=prompt\nz=2882844526 -1h\n");
+    assert!(matches!(parse_error(&sdp), SdpError::OutOfOrder(_)));
+}
+
+#[test]
+fn locates_errors() {
+    let sdp = "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nm=audio port RTP/AVP 0\r\n";
+    let error = parse_error(sdp);
+    assert_eq!(
+        error.location(),
+        Some(&ErrorLocation {
+            line_number: 5,
+            offset: 43,
+            line: String::from("m=audio port RTP/AVP 0"),
+        })
+    );
+    assert_eq!(
+        error.to_string(),
+        "Bad media port format at line 5 (offset 43): m=audio port RTP/AVP 0"
+    );
+
+    let sdp = "v=0\no=- 1 1 IN IP4 192.0.2.1\ns=-\nt=0\n";
+    let location = parse_error(sdp).location().cloned().unwrap();
+    assert_eq!(location.line_number, 4);
+    assert_eq!(location.offset, 33);
+    assert_eq!(location.line, "t=0");
+}
@@ -0,0 +1,200 @@
+use rust_strict_sdp::{
+    file_transfer::{
+        decode_file_name, encode_file_name, file_disposition_attribute, file_icon_attribute,
+        file_transfer_id_attribute, FileDate, FileRange, FileSelector,
+    },
+    AsSDP, Attribute, MediaBuilder, SdpBuilder,
+};
+
+const SDP: &[u8] = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+m=message 7654 TCP/MSRP *\r\n\
+a=sendonly\r\n\
+a=accept-types:message/cpim\r\n\
+a=path:msrp://192.0.2.1:7654/jshA7weztas;tcp\r\n\
+a=file-selector:name:\"My%20cool%20picture.jpg\" type:image/jpeg size:32349 hash:sha-1:72:24:5F:E8:65:3D:DA:F3:71:36:2F:86:D4:71:91:3E:E4:A2:CE:2E\r\n\
+a=file-transfer-id:vBnG916bdberum2fFEABR1FR3ExZMUrd\r\n\
+a=file-disposition:render\r\n\
+a=file-date:creation:\"Mon, 15 May 2006 15:01:31 +0300\"\r\n\
+a=file-icon:cid:id2@alicepc.example.com\r\n\
+a=file-range:1-32349\r\n";
+
+#[test]
+fn parses_file_transfer_attributes() {
+    let sdp = SDP.as_sdp().unwrap();
+    let media = &sdp.medias[0];
+
+    let file_selector = media.file_selector().unwrap();
+    assert_eq!(file_selector.name, Some(&b"My%20cool%20picture.jpg"[..]));
+    assert_eq!(
+        file_selector.decoded_name(),
+        Some(b"My cool picture.jpg".to_vec())
+    );
+    assert_eq!(file_selector.file_type, Some(&b"image/jpeg"[..]));
+    assert_eq!(file_selector.size, Some(32349));
+    let hash = file_selector.hash.unwrap();
+    assert_eq!(hash.algorithm, b"sha-1");
+    assert_eq!(hash.digest.len(), 20);
+    assert_eq!(hash.digest[0], 0x72);
+
+    assert_eq!(
+        media.file_transfer_id(),
+        Some(&b"vBnG916bdberum2fFEABR1FR3ExZMUrd"[..])
+    );
+    assert_eq!(media.file_disposition(), Some(&b"render"[..]));
+    assert_eq!(
+        media.file_date().unwrap().creation,
+        Some(&b"Mon, 15 May 2006 15:01:31 +0300"[..])
+    );
+    assert_eq!(media.file_icon(), Some(&b"cid:id2@alicepc.example.com"[..]));
+    assert_eq!(
+        media.file_range(),
+        Some(FileRange {
+            start: 1,
+            stop: Some(32349),
+        })
+    );
+}
+
+#[test]
+fn rejects_malformed_values() {
+    assert!(FileSelector::parse(b"name:unquoted").is_none());
+    assert!(FileSelector::parse(b"name:\"bad%2\"").is_none());
+    assert!(FileSelector::parse(b"type:jpeg").is_none());
+    assert!(FileSelector::parse(b"size:big").is_none());
+    assert!(FileSelector::parse(b"hash:sha-1:7").is_none());
+    assert!(FileSelector::parse(b"color:blue").is_none());
+
+    assert!(FileDate::parse(b"").is_none());
+    assert!(FileDate::parse(b"creation:unquoted").is_none());
+
+    assert!(FileRange::parse(b"0-10").is_none());
+    assert!(FileRange::parse(b"10-9").is_none());
+    assert!(FileRange::parse(b"10").is_none());
+    assert_eq!(
+        FileRange::parse(b"10-*"),
+        Some(FileRange {
+            start: 10,
+            stop: None,
+        })
+    );
+}
+
+#[test]
+fn encodes_file_names() {
+    let encoded = encode_file_name(b"50% \"off\".txt");
+    assert_eq!(encoded, b"50%25%20%22off%22.txt");
+    assert_eq!(
+        decode_file_name(&encoded),
+        Some(b"50% \"off\".txt".to_vec())
+    );
+}
+
+#[test]
+fn writes_lines_and_values() {
+    let sdp = SDP.as_sdp().unwrap();
+    let media = &sdp.medias[0];
+
+    let file_selector = media.file_selector().unwrap();
+    let mut value = Vec::new();
+    file_selector.write_value_to(&mut value).unwrap();
+    assert_eq!(
+        value,
+        &b"name:\"My%20cool%20picture.jpg\" type:image/jpeg size:32349 hash:sha-1:72:24:5F:E8:65:3D:DA:F3:71:36:2F:86:D4:71:91:3E:E4:A2:CE:2E"[..]
+    );
+    assert_eq!(
+        file_selector.to_string(),
+        format!("a=file-selector:{}\r\n", String::from_utf8_lossy(&value))
+    );
+
+    let mut value = Vec::new();
+    media
+        .file_date()
+        .unwrap()
+        .write_value_to(&mut value)
+        .unwrap();
+    assert_eq!(value, &b"creation:\"Mon, 15 May 2006 15:01:31 +0300\""[..]);
+
+    let mut value = Vec::new();
+    media
+        .file_range()
+        .unwrap()
+        .write_value_to(&mut value)
+        .unwrap();
+    assert_eq!(value, b"1-32349");
+    assert_eq!(
+        FileRange {
+            start: 5,
+            stop: None,
+        }
+        .to_string(),
+        "a=file-range:5-*\r\n"
+    );
+
+    assert_eq!(
+        file_transfer_id_attribute(b"abc").to_string(),
+        "a=file-transfer-id:abc\r\n"
+    );
+    assert_eq!(
+        file_disposition_attribute(b"attachment").to_string(),
+        "a=file-disposition:attachment\r\n"
+    );
+    assert_eq!(
+        file_icon_attribute(b"cid:icon@example.com")
+            .unwrap()
+            .to_string(),
+        "a=file-icon:cid:icon@example.com\r\n"
+    );
+    assert!(file_icon_attribute(b"http://example.com/icon.png").is_none());
+}
+
+#[test]
+fn builds_file_transfer_offer() {
+    let file_selector = FileSelector {
+        name: Some(b"report.pdf"),
+        file_type: Some(b"application/pdf"),
+        size: Some(1024),
+        hash: None,
+    };
+    let mut selector_value = Vec::new();
+    file_selector.write_value_to(&mut selector_value).unwrap();
+    let mut range_value = Vec::new();
+    FileRange {
+        start: 1,
+        stop: Some(1024),
+    }
+    .write_value_to(&mut range_value)
+    .unwrap();
+
+    let sdp = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .connection("192.0.2.1")
+        .media(
+            MediaBuilder::new("message", 7654, "TCP/MSRP")
+                .format("*")
+                .attribute("sendonly")
+                .with_attribute(Attribute {
+                    name: b"file-selector",
+                    value: Some(&selector_value),
+                })
+                .with_attribute(file_transfer_id_attribute(b"id1"))
+                .with_attribute(file_disposition_attribute(b"attachment"))
+                .with_attribute(Attribute {
+                    name: b"file-range",
+                    value: Some(&range_value),
+                }),
+        )
+        .build()
+        .unwrap();
+
+    let written = sdp.to_string();
+    let sdp = written.as_bytes().as_sdp().unwrap();
+    let media = &sdp.medias[0];
+    assert_eq!(media.file_selector(), Some(file_selector));
+    assert_eq!(media.file_transfer_id(), Some(&b"id1"[..]));
+    assert_eq!(media.file_disposition(), Some(&b"attachment"[..]));
+    assert_eq!(media.file_range().unwrap().stop, Some(1024));
+}
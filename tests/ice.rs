@@ -0,0 +1,155 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use rust_strict_sdp::{
+    ice::{CandidateType, IceCandidate, RemoteCandidate},
+    AsSDP,
+};
+
+const SDP: &[u8] = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+a=ice-ufrag:8hhY\r\n\
+a=ice-pwd:asd88fgpdd777uzjYhagZg\r\n\
+a=ice-options:trickle  ice2\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+a=candidate:1 1 UDP 2130706431 192.0.2.1 49170 typ host\r\n\
+a=candidate:2 1 UDP 1694498815 203.0.113.7 61665 typ srflx raddr 192.0.2.1 rport 49170 generation 0\r\n\
+a=candidate:broken\r\n\
+a=remote-candidates:1 203.0.113.9 50000 2 203.0.113.9 50001\r\n\
+m=video 51372 RTP/AVP 31\r\n\
+a=ice-ufrag:Qw3r\r\n\
+a=ice-pwd:Xyzzy0123456789abcdefg\r\n\
+a=ice-options:ice2\r\n\
+a=end-of-candidates\r\n";
+
+#[test]
+fn parses_candidates() {
+    let candidate = IceCandidate::parse(
+        b"2 1 UDP 1694498815 203.0.113.7 61665 typ srflx raddr 192.0.2.1 rport 49170 generation 0",
+    )
+    .unwrap();
+    assert_eq!(candidate.foundation, b"2");
+    assert_eq!(candidate.component, 1);
+    assert_eq!(candidate.transport, b"UDP");
+    assert_eq!(candidate.priority, 1694498815);
+    assert_eq!(
+        candidate.ip_address(),
+        Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)))
+    );
+    assert_eq!(candidate.port, 61665);
+    assert_eq!(candidate.typ, CandidateType::Srflx);
+    assert_eq!(candidate.raddr, Some(&b"192.0.2.1"[..]));
+    assert_eq!(candidate.rport, Some(49170));
+    assert_eq!(candidate.extensions, vec![(&b"generation"[..], &b"0"[..])]);
+
+    let candidate =
+        IceCandidate::parse(b"1 1 TCP 2128609279 host.example.com 9 typ host tcptype active")
+            .unwrap();
+    assert_eq!(candidate.ip_address(), None);
+    assert_eq!(
+        candidate.extensions,
+        vec![(&b"tcptype"[..], &b"active"[..])]
+    );
+
+    let candidate = IceCandidate::parse(b"1 1 UDP 1 192.0.2.1 9 typ x-custom").unwrap();
+    assert_eq!(candidate.typ, CandidateType::Other(b"x-custom"));
+}
+
+#[test]
+fn rejects_malformed_candidates() {
+    for value in [
+        &b""[..],
+        b"1 0 UDP 1 192.0.2.1 9 typ host",
+        b"1 257 UDP 1 192.0.2.1 9 typ host",
+        b"1 1 UDP -1 192.0.2.1 9 typ host",
+        b"1 1 UDP 1 192.0.2.1 65536 typ host",
+        b"1 1 UDP 1 192.0.2.1 9 type host",
+        b"1 1 UDP 1 192.0.2.1 9 typ",
+        b"1 1 UDP 1 192.0.2.1 9 typ host generation",
+        b"1 1 UDP 1 192.0.2.1 9 typ host rport x",
+        b"123456789012345678901234567890123 1 UDP 1 192.0.2.1 9 typ host",
+    ] {
+        assert!(
+            IceCandidate::parse(value).is_none(),
+            "{}",
+            String::from_utf8_lossy(value)
+        );
+    }
+}
+
+#[test]
+fn writes_candidates() {
+    let value =
+        &b"2 1 UDP 1694498815 203.0.113.7 61665 typ srflx raddr 192.0.2.1 rport 49170 generation 0"
+            [..];
+    let candidate = IceCandidate::parse(value).unwrap();
+
+    let mut buf = Vec::new();
+    candidate.write_value_to(&mut buf).unwrap();
+    assert_eq!(buf, value);
+    assert_eq!(
+        candidate.to_string(),
+        format!("a=candidate:{}\r\n", String::from_utf8_lossy(value))
+    );
+}
+
+#[test]
+fn lists_media_candidates() {
+    let sdp = SDP.as_sdp().unwrap();
+
+    let candidates = sdp.medias[0].candidates();
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].typ, CandidateType::Host);
+    assert_eq!(candidates[1].typ, CandidateType::Srflx);
+    assert!(sdp.medias[1].candidates().is_empty());
+}
+
+#[test]
+fn falls_back_to_session_credentials() {
+    let sdp = SDP.as_sdp().unwrap();
+    let audio = &sdp.medias[0];
+    let video = &sdp.medias[1];
+
+    assert_eq!(sdp.ice_ufrag(), Some(&b"8hhY"[..]));
+    assert_eq!(audio.ice_ufrag(&sdp), Some(&b"8hhY"[..]));
+    assert_eq!(audio.ice_pwd(&sdp), Some(&b"asd88fgpdd777uzjYhagZg"[..]));
+    assert_eq!(video.ice_ufrag(&sdp), Some(&b"Qw3r"[..]));
+    assert_eq!(video.ice_pwd(&sdp), Some(&b"Xyzzy0123456789abcdefg"[..]));
+
+    assert_eq!(sdp.ice_options(), vec![&b"trickle"[..], b"ice2"]);
+    assert_eq!(audio.ice_options(&sdp), vec![&b"trickle"[..], b"ice2"]);
+    assert_eq!(video.ice_options(&sdp), vec![&b"ice2"[..]]);
+
+    assert!(!sdp.is_ice_lite());
+    assert!(!audio.is_end_of_candidates(&sdp));
+    assert!(video.is_end_of_candidates(&sdp));
+}
+
+#[test]
+fn parses_remote_candidates() {
+    let sdp = SDP.as_sdp().unwrap();
+
+    let remote_candidates = sdp.medias[0].remote_candidates().unwrap();
+    assert_eq!(
+        remote_candidates,
+        vec![
+            RemoteCandidate {
+                component: 1,
+                address: b"203.0.113.9",
+                port: 50000,
+            },
+            RemoteCandidate {
+                component: 2,
+                address: b"203.0.113.9",
+                port: 50001,
+            },
+        ]
+    );
+    assert!(sdp.medias[1].remote_candidates().is_none());
+
+    assert!(RemoteCandidate::parse_list(b"1 203.0.113.9").is_none());
+    assert!(RemoteCandidate::parse_list(b"1  50000").is_none());
+    assert!(RemoteCandidate::parse_list(b"x 203.0.113.9 50000").is_none());
+}
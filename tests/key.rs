@@ -0,0 +1,94 @@
+use rust_strict_sdp::{AsSDP, KeyMethod, SdpError};
+
+fn session(key: &str) -> String {
+    format!(
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n{}\r\nm=audio 49170 RTP/AVP 0\r\nk=prompt\r\n",
+        key
+    )
+}
+
+#[test]
+fn parses_key_methods_at_both_levels() {
+    let sdp = session("k=base64:c2VjcmV0");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.encryption_key, Some(KeyMethod::Base64(b"c2VjcmV0")));
+    assert_eq!(
+        sdp.encryption_key.as_ref().unwrap().decoded_key(),
+        Some(b"secret".to_vec())
+    );
+    assert_eq!(sdp.medias[0].encryption_key, Some(KeyMethod::Prompt));
+
+    let sdp = session("k=clear:secret");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.encryption_key, Some(KeyMethod::Clear(b"secret")));
+    assert_eq!(
+        sdp.encryption_key.unwrap().decoded_key(),
+        Some(b"secret".to_vec())
+    );
+
+    let sdp = session("k=uri:https://example.com/key");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    let key = sdp.encryption_key.unwrap();
+    assert_eq!(key, KeyMethod::Uri(b"https://example.com/key"));
+    assert_eq!(key.decoded_key(), None);
+}
+
+#[test]
+fn keeps_unknown_key_methods() {
+    let sdp = session("k=x-vendor:opaque");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    let key = sdp.encryption_key.unwrap();
+    assert_eq!(key, KeyMethod::Other(b"x-vendor:opaque"));
+    assert_eq!(key.decoded_key(), None);
+    assert_eq!(key.to_string(), "k=x-vendor:opaque\r\n");
+
+    let sdp = session("k=x-token");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.encryption_key, Some(KeyMethod::Other(b"x-token")));
+}
+
+#[test]
+fn rejects_malformed_known_methods() {
+    for key in [
+        "k=base64:***",
+        "k=clear",
+        "k=uri",
+        "k=prompt:now",
+        "k=:secret",
+        "k=",
+    ] {
+        let sdp = session(key);
+        assert!(
+            matches!(sdp.as_bytes().as_sdp(), Err(SdpError::BadKey(_))),
+            "{}",
+            key
+        );
+    }
+}
+
+#[test]
+fn flags_deprecated_key_lines() {
+    let sdp = session("k=clear:secret");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert!(sdp.encryption_key.as_ref().unwrap().is_deprecated());
+    assert!(sdp.has_deprecated_key());
+
+    let sdp = "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nm=audio 49170 RTP/AVP 0\r\nk=prompt\r\n";
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert!(sdp.encryption_key.is_none());
+    assert!(sdp.has_deprecated_key());
+
+    let sdp = "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nm=audio 49170 RTP/AVP 0\r\n";
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert!(!sdp.has_deprecated_key());
+}
+
+#[test]
+fn writes_key_lines() {
+    let sdp = session("k=base64:c2VjcmV0");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(
+        sdp.to_string(),
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nk=base64:c2VjcmV0\r\nm=audio 49170 RTP/AVP 0\r\nk=prompt\r\n"
+    );
+}
@@ -0,0 +1,83 @@
+use rust_strict_sdp::{AsSDP, Attribute, SdpError};
+
+#[test]
+fn keeps_rejected_stream_without_attributes() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+a=rtpmap:0 PCMU/8000\r\n\
+m=video 0 RTP/AVP 0\r\n";
+
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(sdp.medias.len(), 2);
+    assert_eq!(sdp.medias[1].media_type, b"video");
+    assert_eq!(sdp.medias[1].port, 0);
+    assert_eq!(sdp.medias[1].formats, vec![b"0"]);
+    assert!(sdp.medias[1].attributes.is_empty());
+}
+
+#[test]
+fn keeps_m_line_order_with_attribute_less_streams() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+m=audio 0 RTP/AVP 8\r\n\
+m=video 51372 RTP/AVP 31\r\n\
+c=IN IP4 192.0.2.2\r\n\
+a=sendrecv\r\n\
+m=message 0 TCP/MSRP *\r\n";
+
+    let sdp = sdp.as_sdp().unwrap();
+
+    let media_types: Vec<&[u8]> = sdp.medias.iter().map(|media| media.media_type).collect();
+    assert_eq!(media_types, vec![&b"audio"[..], b"video", b"message"]);
+
+    assert!(sdp.medias[0].connection.is_none());
+    assert!(sdp.medias[0].attributes.is_empty());
+
+    assert!(sdp.medias[1].connection.is_some());
+    assert_eq!(
+        sdp.medias[1].attributes,
+        vec![Attribute::parse(b"sendrecv")]
+    );
+
+    assert!(sdp.medias[2].connection.is_none());
+    assert!(sdp.medias[2].attributes.is_empty());
+}
+
+#[test]
+fn rejects_incomplete_m_lines() {
+    for m_line in [
+        "m=audio 0 RTP/AVP",
+        "m=audio 0 RTP/AVP ",
+        "m=audio 0",
+        "m=audio",
+        "m=",
+    ] {
+        let sdp = format!(
+            "v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+{}\r\n\
+m=video 51372 RTP/AVP 31\r\n",
+            m_line
+        );
+        let error = sdp.as_bytes().as_sdp().unwrap_err();
+        assert!(matches!(error, SdpError::BadMedia(_)), "{}", m_line);
+        let location = error.location().unwrap();
+        assert_eq!(location.line_number, 5);
+        assert_eq!(location.line, m_line);
+    }
+
+    let sdp = "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nm=audio 0 RTP/AVP";
+    assert!(matches!(
+        sdp.as_bytes().as_sdp(),
+        Err(SdpError::BadMedia(_))
+    ));
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use rust_strict_sdp::{
+    msrp::{cema_diagnostics, cema_in_use, MsrpDiagnostic, MsrpUri},
+    AsSDP,
+};
+
+const SDP: &[u8] = b"v=0\r\n\
+o=- 1 1 IN IP4 1.2.3.4\r\n\
+s=-\r\n\
+c=IN IP4 1.2.3.4\r\n\
+t=0 0\r\n\
+m=message 7394 TCP/MSRP *\r\n\
+a=accept-types:message/cpim text/plain\r\n\
+a=accept-wrapped-types:*\r\n\
+a=path:msrp://1.2.3.4:7394/abc/def;tcp\r\n\
+a=max-size:1048576\r\n";
+
+#[test]
+fn parses_msrp_media() {
+    let sdp = SDP.as_sdp().unwrap();
+    let media = &sdp.medias[0];
+    assert!(media.is_msrp());
+
+    let msrp = media.msrp().unwrap();
+    assert!(!msrp.secure);
+    assert_eq!(msrp.path.len(), 1);
+    assert_eq!(msrp.path[0].session_id, Some(&b"abc/def"[..]));
+    assert_eq!(msrp.path[0].transport(), b"tcp");
+    assert_eq!(msrp.accept_types, vec![&b"message/cpim"[..], b"text/plain"]);
+    assert_eq!(msrp.accept_wrapped_types, vec![&b"*"[..]]);
+    assert_eq!(msrp.max_size, Some(1048576));
+
+    assert!(media.msrp_diagnostics(&sdp).is_empty());
+    assert_eq!(
+        media.msrp_transport_address(&sdp, false),
+        Some((&b"1.2.3.4"[..], 7394))
+    );
+}
+
+#[test]
+fn parses_msrp_uris() {
+    let uri = MsrpUri::parse(b"msrps://alice@[2001:db8::1]:2855/s+=/x;tcp;foo=bar").unwrap();
+    assert!(uri.is_secure());
+    assert_eq!(uri.userinfo, Some(&b"alice"[..]));
+    assert_eq!(uri.host, b"[2001:db8::1]");
+    assert_eq!(uri.port, Some(2855));
+    assert_eq!(uri.session_id, Some(&b"s+=/x"[..]));
+    assert_eq!(uri.parameters, vec![&b"foo=bar"[..]]);
+    assert_eq!(
+        uri.to_string(),
+        "msrps://alice@[2001:db8::1]:2855/s+=/x;tcp;foo=bar"
+    );
+
+    let uri = MsrpUri::parse(b"msrp://relay.example.com").unwrap();
+    assert_eq!(uri.port, None);
+    assert_eq!(uri.session_id, None);
+    assert_eq!(uri.transport(), b"tcp");
+
+    for uri in [
+        &b"sip://1.2.3.4:7394/abc;tcp"[..],
+        b"msrp:1.2.3.4:7394/abc;tcp",
+        b"msrp://1.2.3.4:7394/;tcp",
+        b"msrp://1.2.3.4:port/abc;tcp",
+        b"msrp://:7394/abc;tcp",
+        b"msrp://1.2.3.4:7394/abc;",
+    ] {
+        assert!(MsrpUri::parse(uri).is_none());
+    }
+}
+
+#[test]
+fn rejects_bad_msrp_attributes() {
+    for (line, value) in [
+        (
+            "a=path:msrp://1.2.3.4:7394/abc/def;tcp",
+            "a=path:http://1.2.3.4/abc",
+        ),
+        ("a=max-size:1048576", "a=max-size:large"),
+    ] {
+        let sdp = String::from_utf8_lossy(SDP).replace(line, value);
+        let sdp = sdp.as_bytes().as_sdp().unwrap();
+        assert!(sdp.medias[0].msrp().is_none(), "{}", value);
+    }
+
+    let sdp = String::from_utf8_lossy(SDP).replace("TCP/MSRP", "RTP/AVP");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert!(!sdp.medias[0].is_msrp());
+    assert!(sdp.medias[0].msrp().is_none());
+}
+
+#[test]
+fn compares_uris_per_rfc_4975() {
+    let uri = MsrpUri::parse(b"msrp://alice@Example.COM:7394/abc/def;tcp").unwrap();
+
+    for same in [
+        &b"MSRP://example.com:7394/abc/def;TCP"[..],
+        b"msrp://bob@example.com:7394/abc/def;tcp;foo=bar",
+        b"msrp://example.com:7394/abc/def",
+    ] {
+        assert_eq!(uri, MsrpUri::parse(same).unwrap());
+    }
+
+    for different in [
+        &b"msrps://example.com:7394/abc/def;tcp"[..],
+        b"msrp://example.org:7394/abc/def;tcp",
+        b"msrp://example.com/abc/def;tcp",
+        b"msrp://example.com:7395/abc/def;tcp",
+        b"msrp://example.com:7394/ABC/def;tcp",
+        b"msrp://example.com:7394/abc/def;sctp",
+    ] {
+        assert_ne!(uri, MsrpUri::parse(different).unwrap());
+    }
+
+    let mut set = HashSet::new();
+    set.insert(uri);
+    assert!(set.contains(&MsrpUri::parse(b"MSRP://EXAMPLE.com:7394/abc/def").unwrap()));
+}
+
+fn cema_media(body: &str) -> String {
+    format!(
+        "v=0\r\n\
+o=- 1 1 IN IP4 1.2.3.4\r\n\
+s=-\r\n\
+t=0 0\r\n\
+m=message 9000 TCP/MSRP *\r\n\
+c=IN IP4 5.6.7.8\r\n\
+a=path:msrp://1.2.3.4:7394/abc;tcp\r\n\
+{}",
+        body
+    )
+}
+
+#[test]
+fn addresses_msrp_with_and_without_cema() {
+    let sdp = cema_media("a=msrp-cema\r\n");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    let media = &sdp.medias[0];
+
+    assert!(media.has_msrp_cema());
+    assert!(media.msrp_diagnostics(&sdp).is_empty());
+    assert_eq!(
+        media.msrp_transport_address(&sdp, true),
+        Some((&b"5.6.7.8"[..], 9000))
+    );
+    assert_eq!(
+        media.msrp_transport_address(&sdp, false),
+        Some((&b"1.2.3.4"[..], 7394))
+    );
+
+    let sdp = cema_media("");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(
+        sdp.medias[0].msrp_diagnostics(&sdp),
+        vec![MsrpDiagnostic::PathMismatch]
+    );
+}
+
+#[test]
+fn negotiates_cema() {
+    let with_cema = cema_media("a=msrp-cema\r\n");
+    let with_cema = with_cema.as_bytes().as_sdp().unwrap();
+    let without_cema = cema_media("");
+    let without_cema = without_cema.as_bytes().as_sdp().unwrap();
+    let with_cema = &with_cema.medias[0];
+    let without_cema = &without_cema.medias[0];
+
+    assert!(cema_in_use(with_cema, with_cema));
+    assert!(!cema_in_use(with_cema, without_cema));
+    assert!(!cema_in_use(without_cema, with_cema));
+
+    assert!(cema_diagnostics(with_cema, with_cema).is_empty());
+    assert!(cema_diagnostics(with_cema, without_cema).is_empty());
+    assert_eq!(
+        cema_diagnostics(without_cema, with_cema),
+        vec![MsrpDiagnostic::UnsolicitedCema]
+    );
+}
+
+#[test]
+fn reports_cema_outside_msrp() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 1.2.3.4\r\n\
+s=-\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+a=msrp-cema\r\n\
+m=message 9000 TCP/MSRP *\r\n\
+a=accept-types:text/plain\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(
+        sdp.medias[0].msrp_diagnostics(&sdp),
+        vec![MsrpDiagnostic::CemaOutsideMsrp]
+    );
+    assert_eq!(
+        sdp.medias[1].msrp_diagnostics(&sdp),
+        vec![MsrpDiagnostic::BadPath]
+    );
+}
@@ -0,0 +1,86 @@
+use rust_strict_sdp::{AsSDP, SdpBuilder, SdpError, SdpOwned};
+
+fn owned_from_buffer() -> SdpOwned {
+    let buf = b"v=0\n\
+o=- 20518 0 IN IP4 203.0.113.1\n\
+s=Call with Alice\n\
+t=0 0\n\
+m=audio 54400 RTP/AVP 0\n\
+c=IN IP4 203.0.113.1\n\
+a=sendrecv\n"
+        .to_vec();
+    let sdp = buf.as_sdp().unwrap();
+    sdp.to_sdp_owned().unwrap()
+}
+
+#[test]
+fn outlives_the_input_buffer() {
+    let owned = owned_from_buffer();
+    let sdp = owned.sdp();
+    assert_eq!(sdp.session_name, b"Call with Alice");
+    assert_eq!(sdp.medias.len(), 1);
+    assert!(sdp.medias[0].has_flag(b"sendrecv"));
+    assert!(owned.to_string().starts_with("v=0\r\no=- 20518 0 IN IP4"));
+}
+
+#[test]
+fn compares_by_canonical_form() {
+    let lf = b"v=0\no=- 1 1 IN IP4 192.0.2.1\ns=-\nt=0 0\n";
+    let crlf = b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n";
+    let lf = SdpOwned::try_from(&lf[..]).unwrap();
+    let crlf = SdpOwned::try_from(&crlf[..]).unwrap();
+    assert_eq!(lf, crlf);
+    assert_eq!(
+        crlf.as_bytes(),
+        b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n"
+    );
+}
+
+#[test]
+fn rejects_invalid_input_at_creation() {
+    let buf = b"v=0\r\ns=-\r\nt=0 0\r\n";
+    assert_eq!(
+        SdpOwned::try_from(&buf[..]),
+        Err(SdpError::MissingField('o'))
+    );
+}
+
+#[test]
+fn accepts_builder_output() {
+    let sdp = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .session_name("Weekly sync")
+        .build()
+        .unwrap();
+    let owned = SdpOwned::try_from(&sdp).unwrap();
+    assert_eq!(owned.sdp(), sdp);
+
+    let owned = sdp.into_sdp_owned().unwrap();
+    assert_eq!(owned.sdp().session_name, b"Weekly sync");
+
+    let sdp = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .session_name(" ")
+        .build()
+        .unwrap();
+    assert_eq!(sdp.to_sdp_owned().unwrap().sdp().session_name, b" ");
+}
+
+#[test]
+fn rejects_descriptions_the_parser_would_not_accept() {
+    let result = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .session_name("")
+        .build();
+    assert_eq!(result, Err(SdpError::MissingField('s')));
+
+    let mut sdp = SdpBuilder::new()
+        .origin("-", "1", "1", "192.0.2.1")
+        .build()
+        .unwrap();
+    sdp.session_name = b"";
+    assert!(matches!(
+        sdp.to_sdp_owned(),
+        Err(SdpError::BadSessionName(_))
+    ));
+}
@@ -0,0 +1,164 @@
+use rust_strict_sdp::{
+    rtp::{static_payload_type, Fmtp, RtpDiagnostic, RtpMap, STATIC_PAYLOAD_TYPES},
+    AsSDP,
+};
+
+const SDP: &[u8] = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 111 0 101\r\n\
+a=rtpmap:111 opus/48000/2\r\n\
+a=fmtp:111 minptime=10; useinbandfec=1\r\n\
+a=rtpmap:101 telephone-event/8000\r\n\
+a=fmtp:101 0-15\r\n";
+
+#[test]
+fn parses_rtpmap() {
+    let rtpmap = RtpMap::parse(b"111 opus/48000/2").unwrap();
+    assert_eq!(rtpmap.payload_type, 111);
+    assert_eq!(rtpmap.encoding_name, b"opus");
+    assert_eq!(rtpmap.clock_rate, 48000);
+    assert_eq!(rtpmap.channels, Some(2));
+
+    let rtpmap = RtpMap::parse(b"96 H264/90000").unwrap();
+    assert_eq!(rtpmap.channels, None);
+
+    for value in [
+        &b"128 opus/48000"[..],
+        b"x opus/48000",
+        b"96 opus",
+        b"96 opus/fast",
+        b"96 /48000",
+        b"96 opus/48000/",
+        b"96 opus/48000/2/1",
+        b"96",
+    ] {
+        assert!(RtpMap::parse(value).is_none());
+    }
+}
+
+#[test]
+fn parses_fmtp_parameters() {
+    let fmtp = Fmtp::parse(b"111 minptime=10; useinbandfec = 1;;stereo").unwrap();
+    assert_eq!(fmtp.payload_type, 111);
+    assert_eq!(
+        fmtp.parameters,
+        vec![
+            (&b"minptime"[..], Some(&b"10"[..])),
+            (b"useinbandfec", Some(b"1")),
+            (b"stereo", None),
+        ]
+    );
+    assert_eq!(fmtp.get(b"UseInbandFec"), Some(&b"1"[..]));
+    assert_eq!(fmtp.get(b"stereo"), None);
+
+    let fmtp = Fmtp::parse(b"101 0-15").unwrap();
+    assert_eq!(fmtp.raw, b"0-15");
+
+    assert!(Fmtp::parse(b"abc minptime=10").is_none());
+}
+
+#[test]
+fn looks_up_by_payload_type() {
+    let sdp = SDP.as_sdp().unwrap();
+    let media = &sdp.medias[0];
+
+    let rtpmaps = media.rtpmaps();
+    assert_eq!(rtpmaps.keys().copied().collect::<Vec<_>>(), vec![101, 111]);
+    assert_eq!(rtpmaps[&111].encoding_name, b"opus");
+    assert_eq!(media.rtpmap(101), Some(rtpmaps[&101].clone()));
+    assert_eq!(media.rtpmap(0), None);
+
+    let fmtps = media.fmtps();
+    assert_eq!(fmtps[&111].get(b"minptime"), Some(&b"10"[..]));
+    assert_eq!(media.fmtp(101).unwrap().raw, b"0-15");
+    assert!(media.fmtp(0).is_none());
+
+    assert_eq!(media.payload_types().collect::<Vec<_>>(), vec![111, 0, 101]);
+    assert!(media.rtp_diagnostics().is_empty());
+}
+
+#[test]
+fn reports_inconsistent_attributes() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 96\r\n\
+a=rtpmap:96 opus/48000/2\r\n\
+a=rtpmap:96 PCMU/8000\r\n\
+a=rtpmap:97 iLBC/8000\r\n\
+a=rtpmap:98 broken\r\n\
+a=fmtp:99 mode=30\r\n\
+a=fmtp:x mode=30\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+    let media = &sdp.medias[0];
+
+    assert_eq!(
+        media.rtp_diagnostics(),
+        vec![
+            RtpDiagnostic::DuplicatedRtpMap(96),
+            RtpDiagnostic::RtpMapWithoutFormat(97),
+            RtpDiagnostic::MalformedRtpMap(b"98 broken"),
+            RtpDiagnostic::FmtpWithoutFormat(99),
+            RtpDiagnostic::MalformedFmtp(b"x mode=30"),
+        ]
+    );
+    assert_eq!(media.rtpmaps()[&96].encoding_name, b"opus");
+}
+
+#[test]
+fn knows_static_payload_types() {
+    let pcma = static_payload_type(8).unwrap();
+    assert_eq!(pcma.encoding_name, b"PCMA");
+    assert_eq!(pcma.clock_rate, 8000);
+
+    let l16 = static_payload_type(10).unwrap();
+    assert_eq!(l16.channels, Some(2));
+
+    assert!(static_payload_type(1).is_none());
+    assert!(static_payload_type(96).is_none());
+    assert!(STATIC_PAYLOAD_TYPES
+        .iter()
+        .all(|rtpmap| rtpmap.payload_type < 96));
+}
+
+#[test]
+fn lists_codecs_in_m_line_order() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 8 96 0 97 8\r\n\
+a=rtpmap:96 opus/48000/2\r\n\
+a=fmtp:96 useinbandfec=1\r\n\
+a=rtpmap:0 PCMU/16000\r\n\
+m=audio 49172 UDP/BFCP 8\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    let codecs = sdp.medias[0].codecs();
+    let summary: Vec<_> = codecs
+        .iter()
+        .map(|codec| (codec.payload_type, codec.name, codec.clock_rate))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (8, &b"PCMA"[..], 8000),
+            (96, b"opus", 48000),
+            (0, b"PCMU", 16000),
+        ]
+    );
+    assert_eq!(codecs[1].channels, Some(2));
+    assert_eq!(
+        codecs[1].fmtp.as_ref().unwrap().get(b"useinbandfec"),
+        Some(&b"1"[..])
+    );
+    assert!(codecs[0].fmtp.is_none());
+
+    assert!(sdp.medias[1].codecs().is_empty());
+}
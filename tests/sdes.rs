@@ -0,0 +1,160 @@
+use rust_strict_sdp::{
+    sdes::{select_crypto, Crypto, CryptoSuite, FecOrder, KeyLifetime, SessionParam},
+    AsSDP,
+};
+
+const KEY_SALT: &str = "WVNfX19zZW1jdGwgKCkgewkyMjA7fQp9CnVubGVzcyAoc2VsZiAo";
+
+#[test]
+fn parses_crypto_attributes() {
+    let value = format!(
+        "1 AES_CM_128_HMAC_SHA1_80 inline:{}|2^20|1:4;inline:{}|1048576 KDR=1 FEC_ORDER=SRTP_FEC WSH=64",
+        &KEY_SALT[..40],
+        &KEY_SALT[..40]
+    );
+    let crypto = Crypto::parse(value.as_bytes()).unwrap();
+    assert_eq!(crypto.tag, 1);
+    assert_eq!(crypto.suite, CryptoSuite::AesCm128HmacSha1_80);
+    assert_eq!(crypto.key_params.len(), 2);
+
+    let key_param = &crypto.key_params[0];
+    assert_eq!(key_param.key_salt.len(), 30);
+    assert_eq!(key_param.lifetime, Some(KeyLifetime::PowerOfTwo(20)));
+    assert_eq!(key_param.lifetime.unwrap().packets(), 1 << 20);
+    assert_eq!(key_param.mki, Some((&b"1"[..], 4)));
+    assert_eq!(
+        crypto.key_params[1].lifetime,
+        Some(KeyLifetime::Packets(1048576))
+    );
+    assert_eq!(crypto.key_params[1].mki, None);
+
+    assert_eq!(
+        crypto.session_params,
+        vec![
+            SessionParam::Kdr(1),
+            SessionParam::FecOrder(FecOrder::SrtpFec),
+            SessionParam::Wsh(64),
+        ]
+    );
+}
+
+#[test]
+fn keeps_long_mki_values() {
+    let mki = "9".repeat(128);
+    let value = format!(
+        "1 AES_CM_128_HMAC_SHA1_32 inline:{}|{}:128",
+        &KEY_SALT[..40],
+        mki
+    );
+    let crypto = Crypto::parse(value.as_bytes()).unwrap();
+    assert_eq!(crypto.key_params[0].mki, Some((mki.as_bytes(), 128)));
+    assert_eq!(crypto.to_string(), format!("a=crypto:{}\r\n", value));
+
+    let value = format!(
+        "1 AES_CM_128_HMAC_SHA1_32 inline:{}|9{}:4",
+        &KEY_SALT[..40],
+        mki
+    );
+    assert!(Crypto::parse(value.as_bytes()).is_none());
+}
+
+#[test]
+fn rejects_malformed_crypto_attributes() {
+    let key_salt = &KEY_SALT[..40];
+    for value in [
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{}", &KEY_SALT[..36]),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{}|2^64", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{}|1:0", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{}|1:129", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{}|:4", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{}|x:4", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{}|1:4|2^20", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 {}", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{} KDR=25", key_salt),
+        format!("1 AES_CM_128_HMAC_SHA1_80 inline:{} WSH=63", key_salt),
+        format!("1234567890 AES_CM_128_HMAC_SHA1_80 inline:{}", key_salt),
+        "1 AES_CM_128_HMAC_SHA1_80".to_string(),
+    ] {
+        assert!(Crypto::parse(value.as_bytes()).is_none(), "{}", value);
+    }
+}
+
+#[test]
+fn generates_and_answers() {
+    let key_salt: Vec<u8> = (0..30).collect();
+    let crypto = Crypto::generate(1, CryptoSuite::AesCm128HmacSha1_80, key_salt.clone()).unwrap();
+    let written = crypto.to_string();
+    let value = written
+        .strip_prefix("a=crypto:")
+        .unwrap()
+        .strip_suffix("\r\n")
+        .unwrap();
+    assert_eq!(Crypto::parse(value.as_bytes()), Some(crypto));
+
+    assert!(Crypto::generate(1, CryptoSuite::AeadAes128Gcm, key_salt.clone()).is_none());
+    assert!(Crypto::generate(1, CryptoSuite::Other(b"X"), key_salt.clone()).is_none());
+
+    let value = format!(
+        "2 AES_CM_128_HMAC_SHA1_32 inline:{}|1:4 UNENCRYPTED_SRTCP",
+        &KEY_SALT[..40]
+    );
+    let offer = Crypto::parse(value.as_bytes()).unwrap();
+    let answer = offer.answer(key_salt.clone()).unwrap();
+    assert_eq!(answer.tag, 2);
+    assert_eq!(answer.suite, CryptoSuite::AesCm128HmacSha1_32);
+    assert_eq!(answer.key_params[0].key_salt, key_salt);
+    assert_eq!(answer.key_params[0].mki, None);
+    assert_eq!(answer.session_params, vec![SessionParam::UnencryptedSrtcp]);
+}
+
+#[test]
+fn keeps_offered_key_material_out_of_answer() {
+    let offered_key_salt = &KEY_SALT[..40];
+    let fec_key_salt = &KEY_SALT[12..52];
+    let value = format!(
+        "1 AES_CM_128_HMAC_SHA1_80 inline:{}|2^20|1:4 FEC_KEY=inline:{} KDR=1",
+        offered_key_salt, fec_key_salt
+    );
+    let offer = Crypto::parse(value.as_bytes()).unwrap();
+    assert!(offer.session_params.contains(&SessionParam::FecKey(
+        format!("inline:{}", fec_key_salt).as_bytes()
+    )));
+
+    let answer = offer.answer((0..30).collect()).unwrap();
+    assert_eq!(answer.session_params, vec![SessionParam::Kdr(1)]);
+
+    let written = answer.to_string();
+    assert!(!written.contains(offered_key_salt), "{}", written);
+    assert!(!written.contains(fec_key_salt), "{}", written);
+    assert!(!written.contains("FEC_KEY"), "{}", written);
+}
+
+#[test]
+fn selects_offered_crypto() {
+    let sdp = format!(
+        "v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/SAVP 0\r\n\
+a=crypto:1 AES_CM_128_HMAC_SHA1_80 inline:{key_salt} x-unknown\r\n\
+a=crypto:2 AES_CM_128_HMAC_SHA1_80 inline:{key_salt}\r\n\
+a=crypto:3 AES_CM_128_HMAC_SHA1_32 inline:{key_salt}\r\n\
+a=crypto:4 broken\r\n",
+        key_salt = &KEY_SALT[..40]
+    );
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    let offered = sdp.medias[0].cryptos();
+    assert_eq!(offered.len(), 3);
+
+    let selected = select_crypto(
+        &offered,
+        &[
+            CryptoSuite::AesCm128HmacSha1_32,
+            CryptoSuite::AesCm128HmacSha1_80,
+        ],
+    );
+    assert_eq!(selected.unwrap().tag, 2);
+    assert!(select_crypto(&offered, &[CryptoSuite::AeadAes256Gcm]).is_none());
+}
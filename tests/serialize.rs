@@ -0,0 +1,87 @@
+use rust_strict_sdp::AsSDP;
+
+const SDP: &[u8] = b"v=0\r\n\
+o=jdoe 2890844526 2890842807 IN IP4 10.47.16.5\r\n\
+s=SDP Seminar\r\n\
+i=A Seminar on the session description protocol\r\n\
+u=http://www.example.com/seminars/sdp.pdf\r\n\
+e=j.doe@example.com (Jane Doe)\r\n\
+p=+1 617 555-6011\r\n\
+c=IN IP4 224.2.17.12/127\r\n\
+b=CT:128\r\n\
+t=2873397496 2873404696\r\n\
+r=7d 1h 0 25h\r\n\
+z=2882844526 -1h 2898848070 0\r\n\
+k=clear:secret\r\n\
+a=recvonly\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+i=Audio stream\r\n\
+b=AS:64\r\n\
+m=video 51372/2 RTP/AVP 99\r\n\
+c=IN IP6 FF15::101/3\r\n\
+k=prompt\r\n\
+a=rtpmap:99 h263-1998/90000\r\n";
+
+#[test]
+fn writes_back_canonical_input_unchanged() {
+    let sdp = SDP.as_sdp().unwrap();
+
+    let mut buf = Vec::new();
+    sdp.write_to(&mut buf).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        String::from_utf8_lossy(SDP)
+    );
+    assert_eq!(sdp.to_string(), String::from_utf8_lossy(SDP));
+}
+
+#[test]
+fn round_trips_through_the_parser() {
+    let sdp = SDP.as_sdp().unwrap();
+    let written = sdp.to_string();
+    assert_eq!(written.as_bytes().as_sdp().unwrap(), sdp);
+}
+
+#[test]
+fn normalizes_line_endings_to_crlf() {
+    let sdp = b"v=0\no=- 1 1 IN IP4 192.0.2.1\ns=-\nt=0 0\nm=audio 9 RTP/AVP 0\na=sendrecv\n";
+    let sdp = sdp.as_sdp().unwrap();
+    assert_eq!(
+        sdp.to_string(),
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nm=audio 9 RTP/AVP 0\r\na=sendrecv\r\n"
+    );
+}
+
+#[test]
+fn writes_individual_lines() {
+    let sdp = SDP.as_sdp().unwrap();
+    assert_eq!(
+        sdp.origin.to_string(),
+        "o=jdoe 2890844526 2890842807 IN IP4 10.47.16.5\r\n"
+    );
+    assert_eq!(
+        sdp.connection.as_ref().unwrap().to_string(),
+        "c=IN IP4 224.2.17.12/127\r\n"
+    );
+    assert_eq!(
+        sdp.medias[1].to_string(),
+        "m=video 51372/2 RTP/AVP 99\r\n\
+         c=IN IP6 FF15::101/3\r\n\
+         k=prompt\r\n\
+         a=rtpmap:99 h263-1998/90000\r\n"
+    );
+}
+
+#[test]
+fn writes_typed_times_in_compact_form() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=2873397496 2873404696\r\n\
+r=604800 3600 0 90000\r\n\
+z=2882844526 -3600 2898848070 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+    assert!(sdp
+        .to_string()
+        .ends_with("r=7d 1h 0 25h\r\nz=2882844526 -1h 2898848070 0\r\n"));
+}
@@ -0,0 +1,106 @@
+use rust_strict_sdp::{AsSDP, SdpError};
+
+#[test]
+fn parses_session_information_and_media_titles() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+i=A Seminar on the session description protocol\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+i=Main audio: speaker  microphone\r\n\
+m=video 51372 RTP/AVP 31\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(
+        sdp.information,
+        Some(&b"A Seminar on the session description protocol"[..])
+    );
+    assert_eq!(
+        sdp.medias[0].title,
+        Some(&b"Main audio: speaker  microphone"[..])
+    );
+    assert_eq!(sdp.medias[1].title, None);
+}
+
+#[test]
+fn rejects_misplaced_information() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+i=first\r\n\
+i=second\r\n\
+t=0 0\r\n";
+    assert!(matches!(sdp.as_sdp(), Err(SdpError::Duplicated(_))));
+
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+c=IN IP4 192.0.2.1\r\n\
+i=too late\r\n";
+    assert!(matches!(sdp.as_sdp(), Err(SdpError::OutOfOrder(_))));
+}
+
+#[test]
+fn parses_uri_emails_and_phones() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+u=http://www.example.com/seminars/sdp.pdf\r\n\
+e=j.doe@example.com (Jane Doe)\r\n\
+e=Jane Doe <j.doe@example.com>\r\n\
+e=mjh@isi.edu\r\n\
+p=+1 617 555-6011\r\n\
+t=0 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(
+        sdp.uri,
+        Some(&b"http://www.example.com/seminars/sdp.pdf"[..])
+    );
+
+    assert_eq!(sdp.emails.len(), 3);
+    assert_eq!(sdp.emails[0].address, b"j.doe@example.com");
+    assert_eq!(sdp.emails[0].display_name, Some(&b"Jane Doe"[..]));
+    assert_eq!(sdp.emails[1].address, b"j.doe@example.com");
+    assert_eq!(sdp.emails[1].display_name, Some(&b"Jane Doe"[..]));
+    assert_eq!(sdp.emails[2].address, b"mjh@isi.edu");
+    assert_eq!(sdp.emails[2].display_name, None);
+
+    assert_eq!(sdp.phones.len(), 1);
+    assert_eq!(sdp.phones[0].address, b"+1 617 555-6011");
+    assert_eq!(sdp.phones[0].display_name, None);
+
+    assert!(sdp
+        .to_string()
+        .contains("e=j.doe@example.com (Jane Doe)\r\ne=j.doe@example.com (Jane Doe)\r\n"));
+}
+
+#[test]
+fn rejects_misplaced_contacts() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+u=http://example.com/a\r\n\
+u=http://example.com/b\r\n\
+t=0 0\r\n";
+    assert!(matches!(sdp.as_sdp(), Err(SdpError::Duplicated(_))));
+
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+p=+1 617 555-6011\r\n\
+e=j.doe@example.com\r\n\
+t=0 0\r\n";
+    assert!(matches!(sdp.as_sdp(), Err(SdpError::OutOfOrder(_))));
+
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+e=j.doe@example.com\r\n";
+    assert!(matches!(sdp.as_sdp(), Err(SdpError::OutOfOrder(_))));
+}
@@ -0,0 +1,127 @@
+use rust_strict_sdp::{
+    setup::{negotiate, Setup, SetupError, TcpConnection, TcpNegotiation, TcpRole},
+    AsSDP,
+};
+
+fn session(attributes: &str) -> String {
+    format!(
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nc=IN IP4 192.0.2.1\r\nt=0 0\r\nm=message 7654 TCP/MSRP *\r\n{}",
+        attributes
+    )
+}
+
+fn negotiated(offer: &str, answer: &str) -> Result<TcpNegotiation, SetupError> {
+    let offer = session(offer);
+    let answer = session(answer);
+    let offer = offer.as_bytes().as_sdp().unwrap();
+    let answer = answer.as_bytes().as_sdp().unwrap();
+    negotiate(&offer.medias[0], &answer.medias[0])
+}
+
+#[test]
+fn parses_setup_and_connection() {
+    let sdp = session("a=setup:actpass\r\na=connection:existing\r\n");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.medias[0].setup(), Some(Setup::ActPass));
+    assert_eq!(
+        sdp.medias[0].tcp_connection(),
+        Some(TcpConnection::Existing)
+    );
+
+    let sdp = session("a=setup:whatever\r\n");
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+    assert_eq!(sdp.medias[0].setup(), None);
+    assert_eq!(sdp.medias[0].tcp_connection(), None);
+
+    assert_eq!(Setup::HoldConn.to_string(), "holdconn");
+    assert_eq!(TcpConnection::New.to_string(), "new");
+}
+
+#[test]
+fn picks_roles_from_setup() {
+    assert_eq!(
+        negotiated("a=setup:actpass\r\n", "a=setup:active\r\n")
+            .unwrap()
+            .role,
+        TcpRole::Accept
+    );
+    assert_eq!(
+        negotiated("a=setup:actpass\r\n", "a=setup:passive\r\n")
+            .unwrap()
+            .role,
+        TcpRole::Connect
+    );
+    assert_eq!(
+        negotiated("a=setup:active\r\n", "a=setup:holdconn\r\n")
+            .unwrap()
+            .role,
+        TcpRole::Hold
+    );
+}
+
+#[test]
+fn treats_missing_setup_in_answer_as_passive() {
+    assert_eq!(
+        negotiated("a=setup:active\r\n", ""),
+        Ok(TcpNegotiation {
+            role: TcpRole::Connect,
+            reuse_existing: false,
+        })
+    );
+    assert_eq!(
+        negotiated("", "").unwrap().role,
+        TcpRole::Connect,
+        "offer defaults to active"
+    );
+    assert_eq!(
+        negotiated("a=setup:passive\r\n", ""),
+        Err(SetupError::Conflict(Setup::Passive, Setup::Passive))
+    );
+}
+
+#[test]
+fn rejects_answers_that_do_not_fit() {
+    assert_eq!(
+        negotiated("a=setup:actpass\r\n", "a=setup:actpass\r\n"),
+        Err(SetupError::ActPassInAnswer)
+    );
+    assert_eq!(
+        negotiated("a=setup:active\r\n", "a=setup:active\r\n"),
+        Err(SetupError::Conflict(Setup::Active, Setup::Active))
+    );
+    assert_eq!(
+        negotiated("a=setup:active\r\n", "a=setup:sideways\r\n"),
+        Err(SetupError::BadValue(true))
+    );
+    assert_eq!(
+        negotiated("a=connection:old\r\n", ""),
+        Err(SetupError::BadValue(false))
+    );
+}
+
+#[test]
+fn reuses_existing_connection_only_when_both_agree() {
+    assert!(
+        negotiated(
+            "a=setup:active\r\na=connection:existing\r\n",
+            "a=setup:passive\r\na=connection:existing\r\n"
+        )
+        .unwrap()
+        .reuse_existing
+    );
+    assert!(
+        !negotiated(
+            "a=setup:active\r\na=connection:existing\r\n",
+            "a=setup:passive\r\na=connection:new\r\n"
+        )
+        .unwrap()
+        .reuse_existing
+    );
+    assert_eq!(
+        negotiated(
+            "a=setup:active\r\na=connection:new\r\n",
+            "a=setup:passive\r\na=connection:existing\r\n"
+        ),
+        Err(SetupError::ExistingWithoutOffer)
+    );
+}
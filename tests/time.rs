@@ -0,0 +1,244 @@
+use std::{
+    ops::Bound,
+    time::{Duration, SystemTime},
+};
+
+use rust_strict_sdp::{
+    time::{NtpTime, TimeZoneAdjustment},
+    AsSDP, SdpError,
+};
+
+const HOUR: u64 = 3600;
+const DAY: u64 = 24 * HOUR;
+
+fn at(ntp: u64) -> SystemTime {
+    NtpTime::Seconds(ntp).to_system_time().unwrap()
+}
+
+#[test]
+fn keeps_every_time_description_with_its_repeats() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=3034423619 3042462419\r\n\
+r=7d 1h 0 25h\r\n\
+r=604800 3600 1d\r\n\
+t=3042462419 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    assert_eq!(sdp.times.len(), 2);
+    let first = &sdp.times[0];
+    assert_eq!(first.start_time, NtpTime::Seconds(3034423619));
+    assert_eq!(first.stop_time, NtpTime::Seconds(3042462419));
+    assert_eq!(first.repeats.len(), 2);
+    assert_eq!(first.repeats[0].interval, 7 * DAY);
+    assert_eq!(first.repeats[0].active_duration, HOUR);
+    assert_eq!(first.repeats[0].offsets, vec![0, 25 * HOUR]);
+    assert_eq!(first.repeats[1].offsets, vec![DAY]);
+
+    let second = &sdp.times[1];
+    assert_eq!(second.stop_time, NtpTime::Unbounded);
+    assert!(second.repeats.is_empty());
+}
+
+#[test]
+fn lists_occurrences_in_chronological_order() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=3034423619 3035028419\r\n\
+r=7d 1h 0 25h\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    let start = 3034423619;
+    let occurrences: Vec<_> = sdp.times[0].occurrences().collect();
+    assert_eq!(
+        occurrences,
+        vec![
+            (
+                Bound::Included(at(start)),
+                Bound::Excluded(at(start + HOUR))
+            ),
+            (
+                Bound::Included(at(start + 25 * HOUR)),
+                Bound::Excluded(at(start + 26 * HOUR))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn lists_single_interval_without_repeats() {
+    let sdp = b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=3034423619 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    let occurrences: Vec<_> = sdp.times[0].occurrences().collect();
+    assert_eq!(
+        occurrences,
+        vec![(Bound::Included(at(3034423619)), Bound::Unbounded)]
+    );
+
+    let sdp = b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+    let occurrences: Vec<_> = sdp.times[0].occurrences().collect();
+    assert_eq!(occurrences, vec![(Bound::Unbounded, Bound::Unbounded)]);
+}
+
+#[test]
+fn keeps_repeating_without_stop_time() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=3034423619 0\r\n\
+r=1d 1h 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    let starts: Vec<_> = sdp.times[0]
+        .occurrences()
+        .take(3)
+        .map(|(start, _)| start)
+        .collect();
+    assert_eq!(
+        starts,
+        vec![
+            Bound::Included(at(3034423619)),
+            Bound::Included(at(3034423619) + Duration::from_secs(DAY)),
+            Bound::Included(at(3034423619) + Duration::from_secs(2 * DAY)),
+        ]
+    );
+}
+
+#[test]
+fn handles_times_before_the_unix_epoch() {
+    let sdp = b"v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=5 10\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    let time_description = &sdp.times[0];
+    assert_eq!(time_description.start_time, NtpTime::Seconds(5));
+    assert_eq!(time_description.stop_time, NtpTime::Seconds(10));
+
+    let start = SystemTime::UNIX_EPOCH - Duration::from_secs(2208988800 - 5);
+    assert_eq!(time_description.start_time.to_system_time(), Some(start));
+
+    let occurrences: Vec<_> = time_description.occurrences().collect();
+    assert_eq!(
+        occurrences,
+        vec![(
+            Bound::Included(start),
+            Bound::Excluded(start + Duration::from_secs(5))
+        )]
+    );
+    assert_eq!(
+        sdp.to_string(),
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=5 10\r\n"
+    );
+}
+
+#[test]
+fn converts_between_ntp_and_system_time() {
+    assert_eq!(NtpTime::from_ntp(0), NtpTime::Unbounded);
+    assert_eq!(NtpTime::Unbounded.to_ntp(), 0);
+    assert_eq!(NtpTime::Unbounded.to_system_time(), None);
+
+    assert_eq!(
+        NtpTime::try_from(SystemTime::UNIX_EPOCH),
+        Ok(NtpTime::Seconds(2208988800))
+    );
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let ntp = NtpTime::try_from(time).unwrap();
+    assert_eq!(ntp.to_ntp(), 2208988800 + 1_700_000_000);
+    assert_eq!(ntp.to_system_time(), Some(time));
+
+    let epoch = SystemTime::UNIX_EPOCH - Duration::from_secs(2208988800);
+    assert!(NtpTime::try_from(epoch).is_err());
+}
+
+#[test]
+fn rejects_times_out_of_range() {
+    for line in ["t=18446744073709551616 0", "t=3034423620 3034423619"] {
+        let sdp = format!("v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\n{}\r\n", line);
+        assert!(
+            matches!(sdp.as_bytes().as_sdp(), Err(SdpError::TimeOutOfRange(_))),
+            "{}",
+            line
+        );
+    }
+
+    for line in ["t=-1 0", "t=now 0", "t=0"] {
+        let sdp = format!("v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\n{}\r\n", line);
+        assert!(
+            matches!(sdp.as_bytes().as_sdp(), Err(SdpError::BadTime(_))),
+            "{}",
+            line
+        );
+    }
+}
+
+#[test]
+fn parses_time_zone_adjustments() {
+    let sdp = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t=3034423619 0\r\n\
+z=2882844526 -1h 2898848070 0\r\n";
+    let sdp = sdp.as_sdp().unwrap();
+
+    let adjustments = &sdp.time_zone_adjustments;
+    assert_eq!(adjustments.len(), 2);
+    assert_eq!(adjustments[0].at, NtpTime::Seconds(2882844526));
+    assert_eq!(adjustments[0].offset, -3600);
+    assert_eq!(adjustments[1].at, NtpTime::Seconds(2898848070));
+    assert_eq!(adjustments[1].offset, 0);
+
+    assert_eq!(TimeZoneAdjustment::offset_at(adjustments, 2882844525), 0);
+    assert_eq!(
+        TimeZoneAdjustment::offset_at(adjustments, 2882844526),
+        -3600
+    );
+    assert_eq!(TimeZoneAdjustment::offset_at(adjustments, 2898848070), 0);
+
+    for line in ["z=2882844526", "z=2882844526 -1x", "z=-2882844526 1h"] {
+        let sdp = format!(
+            "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\n{}\r\n",
+            line
+        );
+        assert!(
+            matches!(sdp.as_bytes().as_sdp(), Err(SdpError::BadTimeZone(_))),
+            "{}",
+            line
+        );
+    }
+}
+
+#[test]
+fn shifts_repeats_after_an_adjustment() {
+    let start = 3034423619;
+    let sdp = format!(
+        "v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+t={} {}\r\n\
+r=1d 1h 0\r\n\
+z={} -1h\r\n",
+        start,
+        start + 3 * DAY,
+        start + DAY + DAY / 2
+    );
+    let sdp = sdp.as_bytes().as_sdp().unwrap();
+
+    let starts: Vec<_> = sdp.times[0]
+        .adjusted_occurrences(&sdp.time_zone_adjustments)
+        .map(|(start, _)| start)
+        .collect();
+    assert_eq!(
+        starts,
+        vec![
+            Bound::Included(at(start)),
+            Bound::Included(at(start + DAY)),
+            Bound::Included(at(start + 2 * DAY - HOUR)),
+        ]
+    );
+
+    let starts: Vec<_> = sdp.times[0].occurrences().map(|(start, _)| start).collect();
+    assert_eq!(starts[2], Bound::Included(at(start + 2 * DAY)));
+}
@@ -0,0 +1,181 @@
+use rust_strict_sdp::{
+    trickle::{FragmentError, SdpFragment},
+    AsSDP, SdpError,
+};
+
+const SDP: &[u8] = b"v=0\r\n\
+o=- 1 1 IN IP4 192.0.2.1\r\n\
+s=-\r\n\
+c=IN IP4 192.0.2.1\r\n\
+t=0 0\r\n\
+a=ice-ufrag:8hhY\r\n\
+a=ice-pwd:asd88fgpdd777uzjYhagZg\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+a=mid:audio\r\n\
+a=candidate:1 1 UDP 2130706431 192.0.2.1 49170 typ host\r\n\
+m=video 51372 RTP/AVP 31\r\n\
+a=mid:video\r\n";
+
+const FRAGMENT: &[u8] = b"a=ice-ufrag:8hhY\r\n\
+a=ice-pwd:asd88fgpdd777uzjYhagZg\r\n\
+m=audio 9 RTP/AVP 0\r\n\
+a=mid:audio\r\n\
+a=candidate:1 1 UDP 2130706431 192.0.2.1 49170 typ host\r\n\
+a=candidate:2 1 UDP 1694498815 203.0.113.7 61665 typ srflx raddr 192.0.2.1 rport 49170\r\n\
+a=end-of-candidates\r\n";
+
+#[test]
+fn parses_fragments() {
+    let fragment = SdpFragment::parse(FRAGMENT).unwrap();
+    assert_eq!(fragment.attributes.len(), 2);
+    assert_eq!(fragment.medias.len(), 1);
+    assert_eq!(fragment.medias[0].mid(), Some(&b"audio"[..]));
+    assert_eq!(fragment.medias[0].candidates().len(), 2);
+    assert_eq!(fragment.to_string().as_bytes(), FRAGMENT);
+
+    let fragment =
+        SdpFragment::parse(b"a=ice-ufrag:8hhY\nx=ignored\n\nm=audio 9 RTP/AVP 0\n").unwrap();
+    assert_eq!(fragment.attributes.len(), 1);
+    assert_eq!(fragment.medias.len(), 1);
+}
+
+#[test]
+fn locates_malformed_lines() {
+    let err = SdpFragment::parse(b"a=ice-ufrag:8hhY\r\ncandidate\r\n").unwrap_err();
+    assert!(matches!(err, SdpError::BadLine(_)));
+    let location = err.location().unwrap();
+    assert_eq!(location.line_number, 2);
+    assert_eq!(location.offset, 18);
+    assert_eq!(location.line, "candidate");
+    assert_eq!(
+        err.to_string(),
+        "Malformed line at line 2 (offset 18): candidate"
+    );
+
+    let err = SdpFragment::parse(b"a=ice-ufrag:8hhY\r\nm=audio 9 RTP/AVP\r\n").unwrap_err();
+    assert!(matches!(err, SdpError::BadMedia(_)));
+    assert_eq!(err.location().unwrap().line, "m=audio 9 RTP/AVP");
+
+    let err = SdpFragment::parse(b"m=audio x RTP/AVP 0\r\n").unwrap_err();
+    assert!(matches!(err, SdpError::BadMediaPort(_)));
+}
+
+#[test]
+fn applies_fragments_by_mid() {
+    let mut sdp = SDP.as_sdp().unwrap();
+    let fragment = SdpFragment::parse(FRAGMENT).unwrap();
+
+    sdp.apply_fragment(&fragment).unwrap();
+    let audio = &sdp.medias[0];
+    assert_eq!(audio.candidates().len(), 2);
+    assert!(audio.is_end_of_candidates(&sdp));
+    assert!(sdp.medias[1].candidates().is_empty());
+
+    sdp.apply_fragment(&fragment).unwrap();
+    assert_eq!(sdp.medias[0].candidates().len(), 2);
+    assert_eq!(
+        sdp.medias[0]
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.name == b"end-of-candidates")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn applies_fragments_by_index() {
+    let mut sdp = SDP.as_sdp().unwrap();
+    let fragment = SdpFragment::parse(
+        b"m=audio 9 RTP/AVP 0\r\n\
+m=video 9 RTP/AVP 31\r\n\
+a=candidate:1 1 UDP 2130706431 192.0.2.1 51372 typ host\r\n",
+    )
+    .unwrap();
+
+    sdp.apply_fragment(&fragment).unwrap();
+    assert_eq!(sdp.medias[0].candidates().len(), 1);
+    assert_eq!(sdp.medias[1].candidates().len(), 1);
+    assert!(!sdp.medias[1].is_end_of_candidates(&sdp));
+}
+
+#[test]
+fn rejects_fragments_that_do_not_fit() {
+    let mut sdp = SDP.as_sdp().unwrap();
+    let original = sdp.clone();
+
+    let fragment = SdpFragment::parse(b"m=audio 9 RTP/AVP 0\r\na=mid:text\r\n").unwrap();
+    assert_eq!(
+        sdp.apply_fragment(&fragment),
+        Err(FragmentError::UnknownMid("text".to_string()))
+    );
+
+    let fragment = SdpFragment::parse(
+        b"m=audio 9 RTP/AVP 0\r\nm=video 9 RTP/AVP 31\r\nm=text 9 RTP/AVP 98\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        sdp.apply_fragment(&fragment),
+        Err(FragmentError::UnknownIndex(2))
+    );
+
+    let fragment = SdpFragment::parse(
+        b"a=ice-ufrag:Qw3r\r\n\
+m=audio 9 RTP/AVP 0\r\n\
+a=mid:audio\r\n\
+a=candidate:2 1 UDP 1694498815 203.0.113.7 61665 typ srflx\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        sdp.apply_fragment(&fragment),
+        Err(FragmentError::UfragMismatch("Qw3r".to_string()))
+    );
+    assert_eq!(sdp, original);
+}
+
+#[test]
+fn skips_candidates_repeated_in_a_fragment() {
+    let mut sdp = SDP.as_sdp().unwrap();
+    let fragment = SdpFragment::parse(
+        b"m=video 9 RTP/AVP 31\r\n\
+a=mid:video\r\n\
+a=candidate:1 1 UDP 2130706431 192.0.2.1 51372 typ host\r\n\
+a=candidate:1 1 UDP 2130706431 192.0.2.1 51372 typ host\r\n",
+    )
+    .unwrap();
+
+    sdp.apply_fragment(&fragment).unwrap();
+    assert_eq!(sdp.medias[1].candidates().len(), 1);
+    assert_eq!(sdp.medias[1].attributes.len(), 2);
+}
+
+#[test]
+fn ends_candidates_of_every_media() {
+    let mut sdp = SDP.as_sdp().unwrap();
+    let fragment = SdpFragment::parse(b"a=ice-ufrag:8hhY\r\na=end-of-candidates\r\n").unwrap();
+
+    sdp.apply_fragment(&fragment).unwrap();
+    assert!(sdp
+        .medias
+        .iter()
+        .all(|media| media.has_flag(b"end-of-candidates")));
+
+    sdp.apply_fragment(&fragment).unwrap();
+    assert_eq!(
+        sdp.medias[0]
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.name == b"end-of-candidates")
+            .count(),
+        1
+    );
+
+    let mut sdp = SDP.as_sdp().unwrap();
+    let original = sdp.clone();
+    let fragment = SdpFragment::parse(b"a=ice-ufrag:Qw3r\r\na=end-of-candidates\r\n").unwrap();
+    assert_eq!(
+        sdp.apply_fragment(&fragment),
+        Err(FragmentError::UfragMismatch("Qw3r".to_string()))
+    );
+    assert_eq!(sdp, original);
+}